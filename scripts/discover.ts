@@ -22,7 +22,7 @@ async function discover() {
 
     const namespaces = ['tiktok', 'auth', 'user', 'social-accounts', 'integration'];
     const paths = [
-        '/info', '/info?category=gaming', '/stream/current', '/stream/status',
+        '/info', '/info?category=gaming', '/stream/current', '/stream/status', '/stream/history?limit=10',
         '/user', '/user/profile', '/settings', '/categories', '/config'
     ];
 
@@ -1,50 +1,246 @@
 import { 
     API_ENDPOINTS, 
+    DEFAULT_AUDIENCE_TYPES,
+    HTTP_CONFIG,
     QUERY_PARAMS, 
-    CONSOLE_MESSAGES 
+    CONSOLE_MESSAGES,
+    ERROR_MESSAGES
 } from '../constants';
 import { 
+    ApiError,
     BaseApiClient, 
     buildUrl, 
     toFormData, 
     truncate 
 } from '../utils/apiClient';
+import { codePointLength, safeSlice, sanitizeText } from '../utils/strUtils';
 
 export interface StreamInfo {
+    /** Ingest URL to use: the rtmps one when the API provides it */
     rtmpUrl: string;
+    rtmpsUrl?: string;
+    /** Real key for the encoder handoff; log maskStreamKey(streamKey) instead */
     streamKey: string;
     id: string;
 }
 
+export type CurrentStream = Partial<StreamInfo> & { id: string };
+
+export interface UserProfile {
+    username?: string;
+    display_name?: string;
+    avatar_url?: string;
+    avatar_thumb?: string;
+    [key: string]: unknown;
+}
+
+export interface StreamSummary {
+    id: string;
+    title: string;
+    /** ISO timestamps; endedAt is null for a stream that is still live */
+    startedAt: string | null;
+    endedAt: string | null;
+    peakViewers: number;
+}
+
+export interface AudienceType {
+    id: string;
+    label: string;
+}
+
 export interface StreamCategory {
     id: string;
     full_name: string;
     game_mask_id: string;
 }
 
+/**
+ * Read an ingest entry that is either a URL string or an `{ url, key }` object
+ */
+function readIngest(value: unknown): { url?: string; key?: string } {
+    if (typeof value === 'string') return { url: value };
+    if (typeof value === 'object' && value !== null) {
+        const ingest = value as Record<string, unknown>;
+        const url = ingest.url ?? ingest.server;
+        const key = ingest.key ?? ingest.stream_key;
+        return {
+            url: typeof url === 'string' ? url : undefined,
+            key: typeof key === 'string' ? key : undefined,
+        };
+    }
+    return {};
+}
+
+/**
+ * Stream key safe for logs: only the last 4 characters are kept
+ */
+export function maskStreamKey(key: string): string {
+    return codePointLength(key) <= 4 ? '****' : `****${safeSlice(key, -4)}`;
+}
+
+/**
+ * JSON-stringify an API response with stream keys masked
+ */
+function stringifyMasked(value: unknown): string {
+    return JSON.stringify(value, (name, field) =>
+        (name === 'key' || name === 'stream_key') && typeof field === 'string' ? maskStreamKey(field) : field
+    );
+}
+
+/**
+ * Extract stream info from a /stream/start response.
+ * Accepts flat bodies and bodies nested under `stream`, with `rtmp`/`rtmps`
+ * given as strings or objects; `rtmps` is preferred when present.
+ */
+export function parseStreamStartResponse(body: unknown): StreamInfo | null {
+    if (typeof body !== 'object' || body === null) return null;
+    const root = body as Record<string, any>;
+    const containers = [root.stream, root].filter(c => typeof c === 'object' && c !== null);
+
+    for (const container of containers) {
+        const id = container.id ?? container.stream_id ?? root.id;
+        for (const protocol of ['rtmps', 'rtmp']) {
+            const ingest = readIngest(container[protocol] ?? container[`${protocol}_url`]);
+            const key = ingest.key ?? container.key ?? container.stream_key;
+            if (id && ingest.url && key) {
+                return {
+                    rtmpUrl: ingest.url,
+                    ...(protocol === 'rtmps' && { rtmpsUrl: ingest.url }),
+                    streamKey: key,
+                    id: String(id),
+                };
+            }
+        }
+    }
+    return null;
+}
+
+/**
+ * Read a timestamp given as ISO string or unix seconds into an ISO string
+ */
+function readTimestamp(value: unknown): string | null {
+    if (typeof value === 'number' && Number.isFinite(value) && value > 0) {
+        return new Date(value * 1000).toISOString();
+    }
+    if (typeof value === 'string' && value) {
+        const time = Date.parse(value);
+        return Number.isNaN(time) ? null : new Date(time).toISOString();
+    }
+    return null;
+}
+
+/**
+ * Extract stream summaries from a /stream/history response.
+ * Accepts a bare array or one nested under `streams`/`data`; entries without an id are skipped.
+ * These shapes are guesses until a real response is captured (see getStreamHistory).
+ */
+export function parseStreamHistory(body: unknown): StreamSummary[] {
+    const root = body as Record<string, unknown> | null;
+    const list = Array.isArray(body) ? body : root?.streams ?? root?.data;
+    if (!Array.isArray(list)) return [];
+
+    return list.flatMap(entry => {
+        if (typeof entry !== 'object' || entry === null) return [];
+        const stream = entry as Record<string, unknown>;
+        const id = stream.id ?? stream.stream_id;
+        if (id === undefined || id === null || id === '') return [];
+
+        const peak = Number(stream.peak_viewers ?? stream.max_viewers ?? 0);
+        return [{
+            id: String(id),
+            title: typeof stream.title === 'string' ? stream.title : '',
+            startedAt: readTimestamp(stream.started_at ?? stream.start_time),
+            endedAt: readTimestamp(stream.ended_at ?? stream.end_time),
+            peakViewers: Number.isFinite(peak) ? peak : 0,
+        }];
+    });
+}
+
+/**
+ * Extract the audience options from an /info response (`audience_types`, entries
+ * as `{ id|value, label|name }`). Returns an empty list when none are listed.
+ */
+export function parseAudienceTypes(body: unknown): AudienceType[] {
+    const root = body as Record<string, unknown> | null;
+    const list = root?.audience_types ?? root?.audienceTypes;
+    if (!Array.isArray(list)) return [];
+
+    return list.flatMap(entry => {
+        if (typeof entry !== 'object' || entry === null) return [];
+        const option = entry as Record<string, unknown>;
+        const id = option.id ?? option.value;
+        const label = option.label ?? option.name;
+        if ((typeof id !== 'string' && typeof id !== 'number') || typeof label !== 'string') return [];
+        return [{ id: String(id), label }];
+    });
+}
+
+/**
+ * Drop duplicate `game_mask_id`s (first wins) and sort by name, case-insensitively,
+ * so the category list does not jump around between requests
+ */
+export function normalizeCategories(categories: StreamCategory[]): StreamCategory[] {
+    const seen = new Set<string>();
+    return categories
+        .filter(category => {
+            if (seen.has(category.game_mask_id)) return false;
+            seen.add(category.game_mask_id);
+            return true;
+        })
+        .sort((a, b) => a.full_name.localeCompare(b.full_name, undefined, { sensitivity: 'base' }));
+}
+
+/**
+ * Map a Node platform to the `device_platform` value Streamlabs expects
+ */
+export function devicePlatform(platform: NodeJS.Platform = process.platform): 'win32' | 'darwin' | 'linux' {
+    if (platform === 'win32' || platform === 'darwin') return platform;
+    return 'linux';
+}
+
 export class StreamAPI extends BaseApiClient {
     private currentStreamId: string | null = null;
+    private profileCache: { profile: UserProfile; fetchedAt: number } | null = null;
+
+    constructor(token: string, baseUrl: string = API_ENDPOINTS.TIKTOK_BASE, userAgent?: string) {
+        super(baseUrl, token, userAgent);
+    }
+
+    /**
+     * Create a client against a non-production base URL (mock servers, staging)
+     */
+    static withBaseUrl(token: string, baseUrl: string): StreamAPI {
+        return new StreamAPI(token, baseUrl);
+    }
+
+    setToken(token: string): void {
+        super.setToken(token);
+        this.profileCache = null;
+    }
 
-    constructor(token: string) {
-        super(API_ENDPOINTS.TIKTOK_BASE, token);
+    clearToken(): void {
+        super.clearToken();
+        this.profileCache = null;
     }
 
     async search(game: string): Promise<StreamCategory[]> {
-        if (!game) return this.getInitialCategories();
+        const query = game?.trim() ?? '';
+        if (!query) return this.getInitialCategories();
 
-        const truncatedGame = truncate(game.trim(), QUERY_PARAMS.MAX_CATEGORY_LENGTH);
+        const truncatedGame = truncate(query, QUERY_PARAMS.MAX_CATEGORY_LENGTH);
 
-        if (game.trim().length > QUERY_PARAMS.MAX_CATEGORY_LENGTH) {
-            console.log(CONSOLE_MESSAGES.API_SEARCH_TRUNCATED(game.trim(), truncatedGame));
+        if (codePointLength(query) > QUERY_PARAMS.MAX_CATEGORY_LENGTH) {
+            console.log(CONSOLE_MESSAGES.API_SEARCH_TRUNCATED(query, truncatedGame));
         }
 
         console.log(CONSOLE_MESSAGES.API_SEARCH(truncatedGame));
 
+        // encodeURIComponent keeps - _ . ~ literal and encodes spaces as %20
         const response = await this.get<{ categories?: StreamCategory[] }>(
             `/info?category=${encodeURIComponent(truncatedGame)}`
         );
 
-        const results = response?.categories || [];
+        const results = normalizeCategories(response?.categories || []);
         console.log(CONSOLE_MESSAGES.API_SEARCH_RESULTS(truncatedGame, results.length));
         return results;
     }
@@ -53,30 +249,41 @@ export class StreamAPI extends BaseApiClient {
         const response = await this.get<{ categories?: StreamCategory[] }>(
             `/info?category=${QUERY_PARAMS.DEFAULT_CATEGORY}`
         );
-        return (response?.categories || []).slice(0, QUERY_PARAMS.DEFAULT_LIMIT_CATEGORIES);
+        return normalizeCategories(response?.categories || []).slice(0, QUERY_PARAMS.DEFAULT_LIMIT_CATEGORIES);
     }
 
-    async start(title: string, category: string, audienceType: string = QUERY_PARAMS.DEFAULT_AUDIENCE_TYPE): Promise<StreamInfo | null> {
+    /**
+     * Audience options for the start form, falling back to DEFAULT_AUDIENCE_TYPES
+     * when /info fails or does not list them
+     */
+    async getAudienceTypes(): Promise<AudienceType[]> {
+        try {
+            const types = parseAudienceTypes(await this.getStrict<unknown>('/info'));
+            if (types.length > 0) return types;
+        } catch (error) {
+            console.warn(CONSOLE_MESSAGES.API_AUDIENCE_TYPES_FALLBACK, (error as Error)?.message);
+        }
+        return DEFAULT_AUDIENCE_TYPES.map(type => ({ ...type }));
+    }
+
+    async start(title: string, category: string, audienceType: string = QUERY_PARAMS.DEFAULT_AUDIENCE_TYPE): Promise<StreamInfo> {
         const formData = toFormData({
-            title,
-            device_platform: 'win32',
+            title: sanitizeText(title),
+            device_platform: devicePlatform(),
             category,
             audience_type: audienceType,
         });
 
-        const response = await this.post<{ id: string; rtmp: string; key: string }>('/stream/start', formData);
-
-        if (response?.id) {
-            this.currentStreamId = response.id;
-            return {
-                rtmpUrl: response.rtmp,
-                streamKey: response.key,
-                id: response.id
-            };
+        const response = await this.postStrict<unknown>('/stream/start', formData);
+        const info = parseStreamStartResponse(response);
+        if (!info) {
+            console.error(CONSOLE_MESSAGES.API_START_ERROR, stringifyMasked(response));
+            throw new ApiError('missing_data', ERROR_MESSAGES.MISSING_STREAM_INFO);
         }
 
-        console.error(CONSOLE_MESSAGES.API_START_ERROR, response);
-        return null;
+        console.log(CONSOLE_MESSAGES.API_STREAM_STARTED(info.id, info.rtmpUrl, maskStreamKey(info.streamKey)));
+        this.currentStreamId = info.id;
+        return info;
     }
 
     async end(streamId?: string): Promise<boolean> {
@@ -86,22 +293,78 @@ export class StreamAPI extends BaseApiClient {
             return false;
         }
 
-        const response = await this.post<{ success: boolean }>(`/stream/${id}/end`);
-        return response?.success ?? false;
+        const response = await this.postStrict<{ success: boolean }>(`/stream/${id}/end`);
+        const ended = response?.success ?? false;
+        // The app status reports the stream as live while an id is held
+        if (ended && id === this.currentStreamId) {
+            this.currentStreamId = null;
+        }
+        return ended;
+    }
+
+    /**
+     * Most recent broadcasts, newest first as returned by the API.
+     * The endpoint is inferred from the /stream/{start,current,end} routes and has
+     * not been confirmed against a real response yet; scripts/discover.ts probes it
+     * and saves the body, which is what parseStreamHistory should be checked against.
+     */
+    async getStreamHistory(limit: number = QUERY_PARAMS.DEFAULT_HISTORY_LIMIT): Promise<StreamSummary[]> {
+        const response = await this.getStrict<unknown>(`/stream/history?limit=${limit}`);
+        return parseStreamHistory(response).slice(0, limit);
+    }
+
+    getCurrentStreamId(): string | null {
+        return this.currentStreamId;
     }
 
     async getInfo(): Promise<any> {
         const response = await this.get<any>('/info');
-        console.log('[StreamAPI] Info response:', JSON.stringify(response));
+        console.log('[StreamAPI] Info response:', stringifyMasked(response));
         return response;
     }
 
-    async getUserProfile(): Promise<any> {
-        const data = await this.getInfo();
-        return data?.user || null;
+    /**
+     * Returns null when the response has no user; throws ApiError('unauthorized') on 401.
+     * A profile fetched within PROFILE_CACHE_TTL_MS is served without a request.
+     */
+    async getUserProfile(): Promise<UserProfile | null> {
+        const cached = this.profileCache;
+        if (cached && Date.now() - cached.fetchedAt < HTTP_CONFIG.PROFILE_CACHE_TTL_MS) {
+            return cached.profile;
+        }
+
+        const data = await this.getStrict<{ user?: UserProfile }>('/info');
+        const user = data?.user;
+        if (!user) return null;
+
+        const profile = {
+            ...user,
+            avatar_url: user.avatar_url || user.avatar_thumb,
+            avatar_thumb: user.avatar_thumb || user.avatar_url,
+        };
+        this.profileCache = { profile, fetchedAt: Date.now() };
+        return profile;
     }
 
-    async getCurrentStream(): Promise<any> {
-        return await this.get<any>('/stream/current');
+    /**
+     * Fetch the live stream (if any) and adopt its id so it can be ended after a restart.
+     * Returns null only when the API says nothing is live (empty response or 404);
+     * network and other HTTP failures throw ApiError, since the stream may still be up.
+     */
+    async getCurrentStream(): Promise<CurrentStream | null> {
+        let response: any;
+        try {
+            response = await this.getStrict<any>('/stream/current');
+        } catch (error) {
+            if (error instanceof ApiError && error.status === 404) return null;
+            throw error;
+        }
+        const stream = response?.stream ?? response;
+        const id = stream?.id ?? stream?.stream_id;
+        if (!id) return null;
+
+        this.currentStreamId = String(id);
+        const info = parseStreamStartResponse(stream);
+        return info ?? { id: String(id) };
     }
 }
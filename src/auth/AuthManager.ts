@@ -1,27 +1,196 @@
 import crypto from 'node:crypto';
 import path from 'path';
-import { StreamlabsAuth } from './electron-login';
+import { StreamlabsAuth, type AuthEventEmitter } from './electron-login';
 import { 
     AUTH_CONFIG, 
     PATHS, 
-    API_ENDPOINTS, 
-    CONSOLE_MESSAGES 
+    CONSOLE_MESSAGES,
+    ERROR_MESSAGES,
+    HTTP_CONFIG,
+    DEFAULT_AUTH_ENDPOINTS,
+    DEFAULT_AUTH_POLLING,
+    type AuthEndpoints,
+    type PollingOptions
 } from '../constants';
-import { TokenStorage, FileUtils, resolveAppPath } from '../utils/fileUtils';
+import { TokenStorage, FileUtils, resolveDataPath, hasStreamlabsToken } from '../utils/fileUtils';
+import { StreamAPI } from '../api/StreamAPI';
+import { ApiError } from '../utils/apiClient';
+import { retry } from '../utils/retry';
+import { buildHeaders } from '../utils/http';
+import { AuthError } from './errors';
+import { parseTokenResponse, tryParseTokenResponse } from './tokenResponse';
+import { AuthTimer, type AuthMetrics } from './authMetrics';
+import { isValidAuthCode } from './urlUtils';
+import { hasCookiesFile, hasTikTokSession, loadStoredCookies, buildCookieHeader, type TikTokUser } from './cookies';
+
+export type LoginState = 'logged_out' | 'tiktok_logged_in' | 'authenticated' | 'cookies_invalid';
+
+export interface LoginStateResult {
+    state: LoginState;
+    reason?: string;
+}
+
+export interface TokenValidation {
+    valid: boolean;
+    username?: string;
+    reason?: string;
+}
+
+type ProfileApiFactory = (token: string) => Pick<StreamAPI, 'getUserProfile'>;
+
+/**
+ * Check a token against the API without touching any files.
+ * `reason` is `unauthorized` for rejected tokens and the error kind otherwise.
+ */
+export async function validateToken(
+    token: string | null,
+    createStreamApi: ProfileApiFactory = t => new StreamAPI(t)
+): Promise<TokenValidation> {
+    if (!token) {
+        return { valid: false, reason: 'missing_token' };
+    }
+    try {
+        const profile = await createStreamApi(token).getUserProfile();
+        return { valid: true, username: profile?.username };
+    } catch (error) {
+        return { valid: false, reason: ApiError.from(error).kind };
+    }
+}
+
+/**
+ * Report how far the user got through the login flow.
+ * With `validate`, the saved token is checked against the API so stale sessions
+ * surface as `cookies_invalid` instead of failing later.
+ */
+export async function checkLoginState(options: {
+    validate?: boolean;
+    createStreamApi?: ProfileApiFactory;
+} = {}): Promise<LoginStateResult> {
+    const token = new TokenStorage(PATHS.TOKENS).get();
+    const hasCookies = hasCookiesFile();
+
+    if (!token) {
+        return { state: hasCookies ? 'tiktok_logged_in' : 'logged_out' };
+    }
+    if (!options.validate) {
+        return { state: 'authenticated' };
+    }
+
+    const validation = await validateToken(token, options.createStreamApi);
+    if (validation.reason === 'unauthorized') {
+        return { state: 'cookies_invalid', reason: 'unauthorized' };
+    }
+    // Network trouble says nothing about the session itself
+    return { state: 'authenticated', reason: validation.reason };
+}
+
+export interface AppStatus {
+    tiktokCookies: boolean;
+    streamlabsToken: boolean;
+    tokenExpired: boolean;
+    streamLive: boolean;
+}
+
+export interface StartupToken {
+    token: string;
+    /** 'config' for config.json `token` / STREAM_TOKEN, which has no refresh token */
+    source: 'config' | 'tokens';
+}
+
+/**
+ * Token to start the session with: the config value (STREAM_TOKEN or `token` in
+ * config.json) wins over tokens.json, matching the documented override order
+ */
+export function resolveStartupToken(configToken?: string | null): StartupToken | null {
+    const fromConfig = configToken?.trim();
+    if (fromConfig) return { token: fromConfig, source: 'config' };
+    const saved = new TokenStorage(PATHS.TOKENS).get();
+    return saved ? { token: saved, source: 'tokens' } : null;
+}
+
+/**
+ * Single readiness summary for the UI, computed from the credential files
+ * and the in-memory session
+ */
+export function getAppStatus(options: {
+    token?: string | null;
+    streamLive?: boolean;
+    now?: number;
+} = {}): AppStatus {
+    const tokenStorage = new TokenStorage(PATHS.TOKENS);
+    const expiresAt = tokenStorage.getExpiresAt();
+    return {
+        tiktokCookies: hasTikTokSession(loadStoredCookies()),
+        streamlabsToken: hasStreamlabsToken(options.token, PATHS.TOKENS),
+        tokenExpired: expiresAt !== null && expiresAt.getTime() <= (options.now ?? Date.now()),
+        streamLive: Boolean(options.streamLive),
+    };
+}
 
 export class AuthManager {
     private codeVerifier: string;
     private codeChallenge: string;
+    private emit: AuthEventEmitter;
+    private endpoints: AuthEndpoints;
+    private polling: PollingOptions;
+    private activeLogin: StreamlabsAuth | null = null;
+    private loginAttempted = false;
+    private loginIndicators: string[] = [];
+    private capturedUser: TikTokUser | null = null;
+    private authMetrics: AuthMetrics | null = null;
+
+    constructor(options: {
+        emit?: AuthEventEmitter;
+        endpoints?: Partial<AuthEndpoints>;
+        polling?: Partial<PollingOptions>;
+        codeVerifier?: string;
+    } = {}) {
+        this.emit = options.emit ?? (() => {});
+        this.endpoints = { ...DEFAULT_AUTH_ENDPOINTS, ...options.endpoints };
+        this.polling = { ...DEFAULT_AUTH_POLLING, ...options.polling };
+        this.codeVerifier = options.codeVerifier ?? this.generateCodeVerifier();
+        this.codeChallenge = this.generateCodeChallenge(this.codeVerifier);
+    }
 
-    constructor() {
+    /**
+     * Create an AuthManager with a fixed PKCE verifier (deterministic auth URLs for tests)
+     */
+    static withVerifier(codeVerifier: string): AuthManager {
+        return new AuthManager({ codeVerifier });
+    }
+
+    /**
+     * Replace the PKCE verifier/challenge so an abandoned attempt's pair is never reused.
+     * Returns the new challenge.
+     */
+    resetPkce(): string {
         this.codeVerifier = this.generateCodeVerifier();
         this.codeChallenge = this.generateCodeChallenge(this.codeVerifier);
+        return this.codeChallenge;
+    }
+
+    /**
+     * Generate a fresh PKCE pair and return the auth URL built from its challenge.
+     * The next retrieveToken/submitAuthCode uses the matching verifier.
+     */
+    async prepareLogin(): Promise<string> {
+        if (this.isLoginWindowOpen()) {
+            throw new Error(ERROR_MESSAGES.LOGIN_IN_PROGRESS);
+        }
+        this.resetPkce();
+        // Keep this pair for the next login instead of rotating it again
+        this.loginAttempted = false;
+        return this.getAuthUrl();
     }
 
     private generateCodeVerifier(): string {
         return crypto.randomBytes(64).toString('hex');
     }
 
+    /**
+     * S256 challenge of the verifier's ASCII text. Verifiers are never hex-decoded,
+     * so legacy 128-char hex verifiers keep producing the same challenge.
+     */
     private generateCodeChallenge(verifier: string): string {
         const hash = crypto.createHash('sha256').update(verifier).digest();
         return hash.toString('base64')
@@ -38,7 +207,7 @@ export class AuthManager {
             tiktok: '1',
             code_challenge: this.codeChallenge,
         });
-        return `${API_ENDPOINTS.LOGIN_URL}?${params.toString()}`;
+        return `${this.endpoints.loginUrl}?${params.toString()}`;
     }
 
     async retrieveToken(): Promise<string> {
@@ -52,15 +221,152 @@ export class AuthManager {
 
         console.log(CONSOLE_MESSAGES.AUTH_START_FLOW);
 
+        // The first attempt keeps the constructor's (possibly pinned) verifier
+        if (this.loginAttempted) {
+            this.resetPkce();
+        }
+        this.loginAttempted = true;
+
         const authUrl = await this.getAuthUrl();
-        const cookiePathAbs = resolveAppPath(PATHS.COOKIES);
+        const cookiePathAbs = resolveDataPath(PATHS.COOKIES);
+
+        const auth = new StreamlabsAuth(authUrl, cookiePathAbs, this.codeVerifier, this.emit, this.endpoints, this.polling);
+        this.activeLogin = auth;
+        let stored = false;
+        try {
+            const authData = await auth.findToken();
+            const response = parseTokenResponse(authData);
+            tokenStorage.save(response.data);
+            stored = true;
+            console.log(CONSOLE_MESSAGES.AUTH_SAVED);
+            return response.oauthToken;
+        } finally {
+            this.loginIndicators = auth.getLoginIndicators();
+            this.capturedUser = auth.getCapturedUser() ?? this.capturedUser;
+            this.activeLogin = null;
+            this.recordMetrics(auth.getExchangeTimer(), stored);
+        }
+    }
+
+    /**
+     * Which indicators triggered TikTok login detection in the current or last flow
+     */
+    getLoginIndicators(): string[] {
+        return this.activeLogin?.getLoginIndicators() ?? [...this.loginIndicators];
+    }
+
+    /**
+     * TikTok user read from the login window's `user_info`, if one was captured
+     */
+    getCapturedUser(): TikTokUser | null {
+        return this.activeLogin?.getCapturedUser() ?? this.capturedUser;
+    }
+
+    /**
+     * Whether a login window is currently open (false once the user closes it)
+     */
+    isLoginWindowOpen(): boolean {
+        return this.activeLogin?.isWindowOpen() ?? false;
+    }
+
+    /**
+     * Abort the running login flow. Returns false when none is running.
+     */
+    cancelLogin(): boolean {
+        if (!this.activeLogin) return false;
+        this.activeLogin.cancel();
+        return true;
+    }
 
-        const auth = new StreamlabsAuth(authUrl, cookiePathAbs, this.codeVerifier);
-        const authData = await auth.findToken();
+    /**
+     * Inject a debugging script into the login window of the running login flow
+     */
+    async injectCustomScript(label: string, script: string): Promise<void> {
+        if (!this.activeLogin) {
+            throw new Error(ERROR_MESSAGES.NO_LOGIN_WINDOW);
+        }
+        await this.activeLogin.injectCustomScript(label, script);
+    }
+
+    /**
+     * Exchange an authorization code for a token outside the login window
+     * and save it to tokens.json. /auth/data is polled with backoff while it
+     * reports success:false.
+     */
+    async exchangeCodeForToken(code: string): Promise<string> {
+        const timer = new AuthTimer();
+        let stored = false;
+        try {
+            const { result: parsed } = await retry(
+                this.polling,
+                async () => tryParseTokenResponse(await timer.time(() => this.fetchAuthData(code))),
+                { retryOnResult: response => !response }
+            );
+            if (!parsed) {
+                throw new AuthError('timeout', ERROR_MESSAGES.TOKEN_EXCHANGE_FAILED(this.polling.maxAttempts));
+            }
+
+            new TokenStorage(PATHS.TOKENS).save(parsed.data);
+            stored = true;
+            console.log(CONSOLE_MESSAGES.AUTH_SAVED);
+            return parsed.oauthToken;
+        } finally {
+            this.recordMetrics(timer, stored);
+        }
+    }
 
-        tokenStorage.save(authData);
-        console.log(CONSOLE_MESSAGES.AUTH_SAVED);
+    /**
+     * Timings of the last token exchange (also kept when it failed), or null before any
+     */
+    getAuthMetrics(): AuthMetrics | null {
+        return this.authMetrics;
+    }
 
-        return authData.oauth_token;
+    private recordMetrics(timer: AuthTimer | null, stored: boolean): void {
+        if (!timer) return;
+        if (stored) timer.finish();
+        this.authMetrics = timer.snapshot();
+    }
+
+    /**
+     * Exchange a code pasted by the user (when the login window redirect cannot
+     * be captured) using this manager's verifier, i.e. the one in the auth URL
+     */
+    async submitAuthCode(code: string): Promise<string> {
+        const trimmed = code?.trim() ?? '';
+        if (!isValidAuthCode(trimmed)) {
+            throw new AuthError('invalid_code', ERROR_MESSAGES.INVALID_AUTH_CODE);
+        }
+        return this.exchangeCodeForToken(trimmed);
+    }
+
+    private async fetchAuthData(code: string): Promise<unknown> {
+        const params = new URLSearchParams({ code, code_verifier: this.codeVerifier });
+        const url = `${this.endpoints.authDataUrl}?${params.toString()}`;
+        // Carry the session cookies captured during login; /auth/data may require them
+        const cookieHeader = buildCookieHeader(loadStoredCookies(), url);
+        let response: Response;
+        try {
+            response = await fetch(url, {
+                headers: {
+                    ...buildHeaders(),
+                    'Accept': 'application/json',
+                    'X-Requested-With': 'XMLHttpRequest',
+                    ...(cookieHeader && { 'Cookie': cookieHeader }),
+                },
+                signal: AbortSignal.timeout(HTTP_CONFIG.TIMEOUT_MS),
+            });
+        } catch (error) {
+            const timedOut = error instanceof Error && error.name === 'TimeoutError';
+            throw new AuthError(timedOut ? 'timeout' : 'http', `${ERROR_MESSAGES.FETCH_FAILED}: ${(error as Error)?.message}`);
+        }
+        if (!response.ok) {
+            throw new AuthError('http', `${ERROR_MESSAGES.FETCH_FAILED}: HTTP ${response.status}`, response.status);
+        }
+        try {
+            return await response.json();
+        } catch {
+            throw new AuthError('parse', ERROR_MESSAGES.JSON_PARSE_ERROR);
+        }
     }
 }
@@ -0,0 +1,79 @@
+import fs from 'fs';
+import path from 'path';
+import { PATHS } from '../constants';
+import { resolveAppPath, extractOauthToken } from '../utils/fileUtils';
+import { parseJsonCookies, parseTikTokUserInfo, getCapturedUserInfo } from './cookies';
+
+export interface SavedAccount {
+    /** Name of the account's directory */
+    id: string;
+    /** Pass to setCredentialDir to switch to this account */
+    dir: string;
+    /** TikTok user id from the captured user info or cookies, when present */
+    userId?: string;
+    /** TikTok @handle, from the user info captured at login */
+    username?: string;
+    nickname?: string;
+    hasToken: boolean;
+    hasCookies: boolean;
+}
+
+function readFileIfExists(filePath: string): string | null {
+    try {
+        return fs.readFileSync(filePath, 'utf-8');
+    } catch {
+        return null;
+    }
+}
+
+function readAccount(id: string, dir: string): SavedAccount | null {
+    const cookiesContent = readFileIfExists(path.join(dir, PATHS.COOKIES));
+    const tokensContent = readFileIfExists(path.join(dir, PATHS.TOKENS));
+    if (cookiesContent === null && tokensContent === null) return null;
+
+    let cookies: ReturnType<typeof parseJsonCookies> = [];
+    try {
+        cookies = cookiesContent === null ? [] : parseJsonCookies(cookiesContent);
+    } catch {
+        // A broken cookies.json still leaves the account listed
+    }
+    let hasToken = false;
+    try {
+        hasToken = tokensContent !== null && extractOauthToken(JSON.parse(tokensContent)) !== null;
+    } catch {
+        // Same for a broken tokens.json
+    }
+
+    // Only cookies.json predates user_info.json, so older accounts still get an id
+    const userInfoContent = readFileIfExists(path.join(dir, PATHS.USER_INFO));
+    const user = getCapturedUserInfo(cookies, parseTikTokUserInfo(userInfoContent));
+    const profile = user.found ? user.user : undefined;
+    return {
+        id,
+        dir,
+        ...(user.found && { userId: user.userId }),
+        ...(profile?.uniqueId && { username: profile.uniqueId }),
+        ...(profile?.nickname && { nickname: profile.nickname }),
+        hasToken,
+        hasCookies: cookies.length > 0,
+    };
+}
+
+/**
+ * Accounts saved under `root` (default: the `accounts` folder next to the app),
+ * one per subdirectory holding cookies.json and/or tokens.json, sorted by id
+ */
+export function listSavedAccounts(root: string = resolveAppPath(PATHS.ACCOUNTS_DIR)): SavedAccount[] {
+    let entries: fs.Dirent[];
+    try {
+        entries = fs.readdirSync(root, { withFileTypes: true });
+    } catch {
+        return [];
+    }
+
+    return entries
+        .filter(entry => entry.isDirectory())
+        .map(entry => readAccount(entry.name, path.join(root, entry.name)))
+        .filter((account): account is SavedAccount => account !== null)
+        .sort((a, b) => a.id.localeCompare(b.id));
+}
@@ -0,0 +1,38 @@
+/**
+ * Timings of the token exchange, for diagnosing slow logins
+ */
+export interface AuthMetrics {
+    /** Duration of each auth-data request, in order */
+    requestDurationsMs: number[];
+    /** From the auth code being received to the token being stored; null until stored */
+    totalDurationMs: number | null;
+}
+
+/**
+ * Collects AuthMetrics using the monotonic clock, starting when the auth code arrives
+ */
+export class AuthTimer {
+    private readonly startedAt = performance.now();
+    private readonly requests: number[] = [];
+    private total: number | null = null;
+
+    async time<T>(request: () => Promise<T>): Promise<T> {
+        const start = performance.now();
+        try {
+            return await request();
+        } finally {
+            this.requests.push(performance.now() - start);
+        }
+    }
+
+    /**
+     * Mark the token as stored; later calls keep the first value
+     */
+    finish(): void {
+        this.total ??= performance.now() - this.startedAt;
+    }
+
+    snapshot(): AuthMetrics {
+        return { requestDurationsMs: [...this.requests], totalDurationMs: this.total };
+    }
+}
@@ -0,0 +1,48 @@
+export interface CaptureSample {
+    timestamp: number;
+    totalItems: number;
+    loginDetected: boolean;
+}
+
+export type CaptureListener = (sample: CaptureSample) => void;
+
+export const DEFAULT_CAPTURE_STATS_SIZE = 50;
+
+/**
+ * Ring buffer of cookie captures, so flaky logins can be inspected over time
+ */
+export class CaptureStats {
+    private samples: CaptureSample[] = [];
+    private listeners = new Set<CaptureListener>();
+
+    constructor(private readonly capacity: number = DEFAULT_CAPTURE_STATS_SIZE) {}
+
+    record(totalItems: number, loginDetected: boolean, timestamp: number = Date.now()): void {
+        const sample = { timestamp, totalItems, loginDetected };
+        this.samples.push(sample);
+        if (this.samples.length > this.capacity) {
+            this.samples.shift();
+        }
+        this.listeners.forEach(listener => listener(sample));
+    }
+
+    /**
+     * Get notified of every new capture instead of polling series().
+     * Returns a function that removes the listener.
+     */
+    subscribe(listener: CaptureListener): () => void {
+        this.listeners.add(listener);
+        return () => {
+            this.listeners.delete(listener);
+        };
+    }
+
+    /**
+     * Samples oldest first
+     */
+    series(): CaptureSample[] {
+        return [...this.samples];
+    }
+}
+
+export const captureStats = new CaptureStats();
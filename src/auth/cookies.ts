@@ -0,0 +1,365 @@
+import fs from 'fs';
+import type { CookiesSetDetails } from 'electron';
+import { FileUtils, deleteCredentialFile } from '../utils/fileUtils';
+import { buildHeaders } from '../utils/http';
+import { PATHS, API_ENDPOINTS, AUTH_CONFIG, HTTP_CONFIG, ERROR_MESSAGES, CONSOLE_MESSAGES, TIKTOK_SESSION_COOKIES } from '../constants';
+
+/**
+ * Cookie shape stored in cookies.json (as accepted by Electron's cookies.set)
+ */
+export interface StoredCookie {
+    name: string;
+    value: string;
+    domain: string;
+    path: string;
+    secure: boolean;
+    httpOnly: boolean;
+    expirationDate?: number;
+}
+
+export type CookieFileFormat = 'json' | 'netscape';
+
+/**
+ * Parse a JSON cookie export (Electron or common browser extension shape).
+ * Oversized content is rejected before parsing.
+ */
+export function parseJsonCookies(content: string, maxBytes: number = AUTH_CONFIG.MAX_PAYLOAD_BYTES): StoredCookie[] {
+    if (Buffer.byteLength(content, 'utf-8') > maxBytes) {
+        throw new Error(ERROR_MESSAGES.PAYLOAD_TOO_LARGE(maxBytes));
+    }
+    const data = JSON.parse(content);
+    if (!Array.isArray(data)) {
+        throw new Error(ERROR_MESSAGES.INVALID_COOKIE_FILE);
+    }
+
+    return data
+        .filter(c => c && typeof c.name === 'string' && typeof c.domain === 'string')
+        .map(c => ({
+            name: c.name,
+            value: String(c.value ?? ''),
+            domain: c.domain,
+            path: c.path || '/',
+            secure: Boolean(c.secure),
+            httpOnly: Boolean(c.httpOnly),
+            ...(typeof (c.expirationDate ?? c.expires) === 'number' && { expirationDate: c.expirationDate ?? c.expires }),
+        }));
+}
+
+/**
+ * Parse a Netscape/curl cookies.txt file
+ */
+export function parseNetscapeCookies(content: string): StoredCookie[] {
+    const cookies: StoredCookie[] = [];
+
+    for (const rawLine of content.split(/\r?\n/)) {
+        let line = rawLine.trim();
+        let httpOnly = false;
+        if (line.startsWith('#HttpOnly_')) {
+            httpOnly = true;
+            line = line.substring('#HttpOnly_'.length);
+        } else if (!line || line.startsWith('#')) {
+            continue;
+        }
+
+        const [domain, , path, secure, expires, name, value = ''] = line.split('\t');
+        if (!domain || !name) continue;
+
+        const expirationDate = Number(expires);
+        cookies.push({
+            name,
+            value,
+            domain,
+            path: path || '/',
+            secure: secure?.toUpperCase() === 'TRUE',
+            httpOnly,
+            ...(expirationDate > 0 && { expirationDate }),
+        });
+    }
+
+    return cookies;
+}
+
+/**
+ * Load cookies.json, treating a missing, truncated or hand-broken file as "no cookies".
+ * The import size cap does not apply: the file is written by the app itself.
+ */
+export function loadStoredCookies(filename: string = PATHS.COOKIES): StoredCookie[] {
+    const content = FileUtils.read(filename);
+    if (content === null) return [];
+
+    try {
+        return parseJsonCookies(content, Number.POSITIVE_INFINITY);
+    } catch (error) {
+        console.warn(CONSOLE_MESSAGES.COOKIES_MALFORMED(filename), error);
+        return [];
+    }
+}
+
+/**
+ * A cookies.json entry in the Electron `Cookie` shape written by the login window
+ */
+export interface SessionCookie extends StoredCookie {
+    sameSite?: CookiesSetDetails['sameSite'];
+    hostOnly?: boolean;
+}
+
+const SAME_SITE_VALUES = ['unspecified', 'no_restriction', 'lax', 'strict'];
+
+/**
+ * Load cookies.json for restoring the login window session. Unlike
+ * loadStoredCookies (the import normalizer) this keeps Electron's sameSite,
+ * hostOnly and expiry fields.
+ */
+export function loadSessionCookies(filename: string = PATHS.COOKIES): SessionCookie[] {
+    const content = FileUtils.read(filename);
+    if (content === null) return [];
+
+    let data: unknown;
+    try {
+        data = JSON.parse(content);
+    } catch (error) {
+        console.warn(CONSOLE_MESSAGES.COOKIES_MALFORMED(filename), error);
+        return [];
+    }
+    if (!Array.isArray(data)) return [];
+
+    return data
+        .filter(c => c && typeof c.name === 'string' && typeof c.domain === 'string')
+        .map(c => ({
+            name: c.name,
+            value: String(c.value ?? ''),
+            domain: c.domain,
+            path: c.path || '/',
+            secure: Boolean(c.secure),
+            httpOnly: Boolean(c.httpOnly),
+            ...(typeof c.expirationDate === 'number' && { expirationDate: c.expirationDate }),
+            ...(SAME_SITE_VALUES.includes(c.sameSite) && { sameSite: c.sameSite }),
+            ...(typeof c.hostOnly === 'boolean' && { hostOnly: c.hostOnly }),
+        }));
+}
+
+/**
+ * `session.cookies.set` details for a stored cookie. Host-only cookies are set
+ * without a domain so Electron does not widen them to subdomains; cookies
+ * without an expiry stay session cookies.
+ */
+export function toCookieSetDetails(cookie: SessionCookie): CookiesSetDetails {
+    const host = cookie.domain.startsWith('.') ? cookie.domain.substring(1) : cookie.domain;
+    return {
+        url: `${cookie.secure ? 'https' : 'http'}://${host}${cookie.path}`,
+        name: cookie.name,
+        value: cookie.value,
+        path: cookie.path,
+        secure: cookie.secure,
+        httpOnly: cookie.httpOnly,
+        ...(!cookie.hostOnly && { domain: cookie.domain }),
+        ...(cookie.expirationDate !== undefined && { expirationDate: cookie.expirationDate }),
+        ...(cookie.sameSite && { sameSite: cookie.sameSite }),
+    };
+}
+
+/**
+ * Whether cookies.json exists and holds at least one usable cookie
+ */
+export function hasCookiesFile(filename: string = PATHS.COOKIES): boolean {
+    return loadStoredCookies(filename).length > 0;
+}
+
+/**
+ * Check whether the cookies contain a TikTok login session
+ */
+export function hasTikTokSession(cookies: StoredCookie[]): boolean {
+    return cookies.some(c =>
+        c.domain.includes('tiktok.com') &&
+        (TIKTOK_SESSION_COOKIES as readonly string[]).includes(c.name) &&
+        c.value.length > 0
+    );
+}
+
+/**
+ * RFC 6265 path-match: `/api` covers `/api` and `/api/x` but not `/apix`
+ */
+function pathMatches(requestPath: string, cookiePath: string): boolean {
+    if (!cookiePath || requestPath === cookiePath) return true;
+    if (!requestPath.startsWith(cookiePath)) return false;
+    return cookiePath.endsWith('/') || requestPath[cookiePath.length] === '/';
+}
+
+/**
+ * Build a `Cookie` request header from the stored cookies that apply to `url`
+ * (host/domain and path match, secure cookies only over https, expired cookies
+ * skipped). Returns null when none apply.
+ */
+export function buildCookieHeader(cookies: StoredCookie[], url: string): string | null {
+    let target: URL;
+    try {
+        target = new URL(url);
+    } catch {
+        return null;
+    }
+    const host = target.hostname;
+    const https = target.protocol === 'https:';
+
+    const now = Date.now() / 1000;
+    const matching = cookies.filter(c => {
+        const domain = c.domain.replace(/^\./, '');
+        const domainMatches = host === domain || host.endsWith(`.${domain}`);
+        const expired = c.expirationDate !== undefined && c.expirationDate < now;
+        return domainMatches && pathMatches(target.pathname, c.path) && (https || !c.secure) && !expired;
+    });
+
+    return matching.length > 0 ? matching.map(c => `${c.name}=${c.value}`).join('; ') : null;
+}
+
+export interface CookieValidation {
+    cookieCount: number;
+    hasSession: boolean;
+    missing: string[];
+}
+
+/**
+ * Report which TikTok session cookies are missing, so an incomplete capture
+ * gives actionable feedback instead of a bare "not logged in"
+ */
+export function validateCookies(cookies: StoredCookie[]): CookieValidation {
+    const present = new Set(
+        cookies.filter(c => c.domain.includes('tiktok.com') && c.value).map(c => c.name)
+    );
+    return {
+        cookieCount: cookies.length,
+        hasSession: hasTikTokSession(cookies),
+        missing: TIKTOK_SESSION_COOKIES.filter(name => !present.has(name)),
+    };
+}
+
+export interface CookieVerification {
+    valid: boolean;
+    reason?: 'no_cookies' | 'redirected_to_login' | 'unauthorized' | 'not_logged_in' | 'network';
+}
+
+/**
+ * Make a lightweight authenticated TikTok request with the saved cookies to
+ * confirm the session is still live. A redirect to the login page means it expired.
+ */
+export async function verifyTikTokCookies(
+    url: string = API_ENDPOINTS.TIKTOK_ACCOUNT_INFO,
+    cookies: StoredCookie[] = loadStoredCookies()
+): Promise<CookieVerification> {
+    const cookieHeader = buildCookieHeader(cookies, url);
+    if (!cookieHeader) return { valid: false, reason: 'no_cookies' };
+
+    let response: Response;
+    try {
+        response = await fetch(url, {
+            redirect: 'manual',
+            headers: { ...buildHeaders(), 'Accept': 'application/json', 'Cookie': cookieHeader },
+            signal: AbortSignal.timeout(HTTP_CONFIG.CONNECTIVITY_TIMEOUT_MS),
+        });
+    } catch {
+        return { valid: false, reason: 'network' };
+    }
+
+    if (response.status >= 300 && response.status < 400) {
+        const location = response.headers.get('location') ?? '';
+        return location.includes('login') ? { valid: false, reason: 'redirected_to_login' } : { valid: false, reason: 'not_logged_in' };
+    }
+    if (response.status === 401 || response.status === 403) {
+        return { valid: false, reason: 'unauthorized' };
+    }
+
+    const body = await response.json().catch(() => null) as { message?: string; data?: { user_id?: unknown } } | null;
+    return response.ok && body?.message !== 'error' && body?.data?.user_id
+        ? { valid: true }
+        : { valid: false, reason: 'not_logged_in' };
+}
+
+/**
+ * TikTok user from the web app's `user_info` localStorage entry
+ */
+export interface TikTokUser {
+    uid: string;
+    nickname?: string;
+    secUid?: string;
+    uniqueId?: string;
+}
+
+/**
+ * Parse the `user_info` localStorage value, given as the raw JSON string or
+ * already parsed. Returns null for non-JSON values or a blob without a uid;
+ * other fields are kept only when they are non-empty strings.
+ */
+export function parseTikTokUserInfo(value: unknown): TikTokUser | null {
+    let data = value;
+    if (typeof data === 'string') {
+        try {
+            data = JSON.parse(data);
+        } catch {
+            return null;
+        }
+    }
+    if (typeof data !== 'object' || data === null || Array.isArray(data)) return null;
+
+    const info = data as Record<string, unknown>;
+    const uid = info.uid ?? info.user_id;
+    if ((typeof uid !== 'string' || !uid) && typeof uid !== 'number') return null;
+
+    const text = (field: unknown) => typeof field === 'string' && field ? field : undefined;
+    const nickname = text(info.nickname);
+    const secUid = text(info.secUid ?? info.sec_uid);
+    const uniqueId = text(info.uniqueId ?? info.unique_id);
+    return {
+        uid: String(uid),
+        ...(nickname && { nickname }),
+        ...(secUid && { secUid }),
+        ...(uniqueId && { uniqueId }),
+    };
+}
+
+export type CapturedUserInfo =
+    | { found: true; userId: string; user?: TikTokUser }
+    | { found: false };
+
+/**
+ * Derive the TikTok user from the captured `user_info` (when available) or cookies.
+ * `multi_sids` holds `<uid>:<sessionid>` pairs separated by `;` (URL-encoded).
+ */
+export function getCapturedUserInfo(cookies: StoredCookie[], user: TikTokUser | null = null): CapturedUserInfo {
+    if (user) return { found: true, userId: user.uid, user };
+
+    const multiSids = cookies.find(c => c.name === 'multi_sids' && c.domain.includes('tiktok.com'));
+    if (!multiSids?.value) return { found: false };
+
+    let decoded = multiSids.value;
+    try {
+        decoded = decodeURIComponent(decoded);
+    } catch {
+        // Keep the raw value; the id is plain digits either way
+    }
+
+    const userId = decoded.split(';')[0]?.split(':')[0]?.trim();
+    return userId && /^\d+$/.test(userId) ? { found: true, userId } : { found: false };
+}
+
+/**
+ * Import cookies exported from a browser into cookies.json
+ */
+export function importCookies(filePath: string, format: CookieFileFormat): StoredCookie[] {
+    const content = fs.readFileSync(filePath, 'utf-8');
+    const cookies = format === 'netscape' ? parseNetscapeCookies(content) : parseJsonCookies(content);
+
+    if (!hasTikTokSession(cookies)) {
+        throw new Error(ERROR_MESSAGES.NO_TIKTOK_SESSION);
+    }
+
+    FileUtils.writeJson(PATHS.COOKIES, cookies);
+    return cookies;
+}
+
+/**
+ * Forget the captured TikTok cookies and user info while keeping tokens.json, so
+ * only the TikTok half of the login has to be redone. Returns whether cookies.json was removed.
+ */
+export function clearTikTokCookies(): boolean {
+    deleteCredentialFile(PATHS.USER_INFO);
+    return deleteCredentialFile(PATHS.COOKIES);
+}
@@ -1,16 +1,30 @@
 import * as electron from 'electron';
 const { BrowserWindow, session, ipcMain } = electron;
 import type { IpcMainEvent, WebContents } from 'electron';
+import path from 'path';
 import { 
     IPC_CHANNELS, 
     WINDOW_CONFIG, 
+    AUTH_CONFIG,
     PATHS, 
-    API_ENDPOINTS, 
     ERROR_MESSAGES, 
     CONSOLE_MESSAGES,
-    USER_AGENT
+    USER_AGENT,
+    DEFAULT_AUTH_ENDPOINTS,
+    DEFAULT_AUTH_POLLING,
+    DEFAULT_LOGIN_INDICATORS,
+    type AuthEndpoints,
+    type PollingOptions,
+    type LoginIndicators
 } from '../constants';
-import { FileUtils,getAppBasePath } from '../utils/fileUtils';
+import { FileUtils, getAppBasePath, extractAuthData } from '../utils/fileUtils';
+import { classifyUrl } from './urlUtils';
+import { loadSessionCookies, toCookieSetDetails, hasTikTokSession, parseTikTokUserInfo, type StoredCookie, type TikTokUser } from './cookies';
+import { captureStats } from './captureStats';
+import { startSpan, type Span } from '../utils/trace';
+import { retry, type RetryOutcome } from '../utils/retry';
+import { AuthTimer } from './authMetrics';
+import { AuthError, type AuthErrorKind } from './errors';
 
 interface AuthResult {
     success: boolean;
@@ -20,8 +34,141 @@ interface AuthResult {
     body?: string;
 }
 
+function failureKind(result: AuthResult): AuthErrorKind {
+    if (result.error === ERROR_MESSAGES.WINDOW_CLOSED) return 'window_closed';
+    if (result.error === ERROR_MESSAGES.JSON_PARSE_ERROR) return 'parse';
+    if (result.success) return 'token_missing';
+    return 'http';
+}
+
+export type TokenExchangeStatus = 'request_sent' | 'response_received' | 'token_extracted';
+
+/**
+ * Forwards token exchange events (see IPC_CHANNELS.TOKEN_EXCHANGE_*) to the UI
+ */
+export type AuthEventEmitter = (channel: string, payload: unknown) => void;
+
+const NAVIGATION_PATTERNS = [
+    /\blocation\s*(?:\.href\s*)?=(?!=)/,
+    /\blocation\.(?:assign|replace)\s*\(/,
+    /\bwindow\.open\s*\(/,
+];
+
+/**
+ * Check a caller-supplied debugging script before it is injected into the login window.
+ * Oversized scripts and obvious navigation mistakes are rejected. This is not a
+ * security check: the patterns are trivially bypassed (e.g. `window['loc' + 'ation']`),
+ * which is why injection is only exposed in dev builds or behind debugScriptInjection.
+ */
+export function validateCustomScript(script: string, maxBytes: number = AUTH_CONFIG.CUSTOM_SCRIPT_MAX_BYTES): void {
+    if (Buffer.byteLength(script, 'utf-8') > maxBytes) {
+        throw new Error(ERROR_MESSAGES.SCRIPT_TOO_LARGE(maxBytes));
+    }
+    if (NAVIGATION_PATTERNS.some(pattern => pattern.test(script))) {
+        throw new Error(ERROR_MESSAGES.SCRIPT_UNSAFE);
+    }
+}
+
+/**
+ * Script run in TikTok pages that returns the login indicators present,
+ * as `{ selectors, cookies }` (see parseLoginIndicators). The login form can
+ * render inside an iframe, so accessible same-origin frames are checked too;
+ * cross-origin frames throw on access and are skipped.
+ */
+export function buildLoginCheckScript(indicators: LoginIndicators = DEFAULT_LOGIN_INDICATORS): string {
+    return `
+    (function() {
+        const selectors = ${JSON.stringify(indicators.selectors)};
+        const cookieNames = ${JSON.stringify(indicators.cookieNames)};
+        const documents = [document];
+        for (let i = 0; i < window.frames.length; i++) {
+            try {
+                const frameDocument = window.frames[i].document;
+                if (frameDocument) documents.push(frameDocument);
+            } catch (e) { /* cross-origin frame */ }
+        }
+        const matchedSelectors = selectors.filter(function(selector) {
+            return documents.some(function(doc) {
+                try { return doc.querySelector(selector) !== null; } catch (e) { return false; }
+            });
+        });
+        const names = [];
+        documents.forEach(function(doc) {
+            try {
+                doc.cookie.split(';').forEach(function(c) { names.push(c.split('=')[0].trim()); });
+            } catch (e) { /* cookies not readable in this frame */ }
+        });
+        const matchedCookies = cookieNames.filter(function(name) { return names.indexOf(name) !== -1; });
+        return { selectors: matchedSelectors, cookies: matchedCookies };
+    })()
+    `;
+}
+
+/**
+ * Turn the login check script result into indicator labels
+ * (`user_element:<selector>`, `cookie:<name>`); empty means not logged in
+ */
+export function parseLoginIndicators(result: unknown): string[] {
+    if (typeof result !== 'object' || result === null) return [];
+    const { selectors, cookies } = result as { selectors?: unknown; cookies?: unknown };
+    const strings = (value: unknown) => Array.isArray(value) ? value.filter((v): v is string => typeof v === 'string') : [];
+    return [
+        ...strings(selectors).map(selector => `user_element:${selector}`),
+        ...strings(cookies).map(name => `cookie:${name}`),
+    ];
+}
+
+/**
+ * Remove the login window's in-browser cookies for a domain (e.g. stale TikTok cookies)
+ */
+export async function clearBrowserCookies(domain: string = 'tiktok.com'): Promise<number> {
+    const cookies = await session.defaultSession.cookies.get({ domain });
+    await Promise.all(cookies.map(cookie => {
+        const host = (cookie.domain ?? domain).replace(/^\./, '');
+        const url = `${cookie.secure ? 'https' : 'http'}://${host}${cookie.path ?? '/'}`;
+        return session.defaultSession.cookies.remove(url, cookie.name);
+    }));
+    return cookies.length;
+}
+
+type IpcListener = (event: IpcMainEvent, ...args: any[]) => void;
+
+/**
+ * Owns the login window and its IPC listeners. dispose() closes the window and
+ * unregisters the listeners exactly once, however the flow ends (success,
+ * failure, user closing the window or cancellation).
+ */
+export class LoginSession {
+    private listeners: Array<[string, IpcListener]> = [];
+    private disposed = false;
+
+    constructor(private readonly window: Pick<typeof BrowserWindow.prototype, 'close' | 'isDestroyed'>) {}
+
+    listen(channel: string, listener: IpcListener): void {
+        ipcMain.on(channel, listener);
+        this.listeners.push([channel, listener]);
+    }
+
+    isDisposed(): boolean {
+        return this.disposed;
+    }
+
+    dispose(): void {
+        if (this.disposed) return;
+        this.disposed = true;
+        for (const [channel, listener] of this.listeners) {
+            ipcMain.removeListener(channel, listener);
+        }
+        this.listeners = [];
+        if (!this.window.isDestroyed()) {
+            this.window.close();
+        }
+    }
+}
+
 export class StreamlabsAuth {
     private window: typeof BrowserWindow.prototype | null = null;
+    private loginSession: LoginSession | null = null;
     private authUrl: string;
     private cookiesPath: string;
     private codeVerifier: string;
@@ -29,13 +176,49 @@ export class StreamlabsAuth {
     private resolveToken: ((value: string) => void) | null = null;
     private rejectToken: ((reason: any) => void) | null = null;
 
-    constructor(authUrl: string, cookiesPath: string, codeVerifier: string) {
+    private emit: AuthEventEmitter;
+    private endpoints: AuthEndpoints;
+    private polling: PollingOptions;
+    private indicators: LoginIndicators;
+    private loginSpan: Span | null = null;
+    private exchangeSpan: Span | null = null;
+    private navigations: number = 0;
+    private lastNavigation: { url: string; at: number } | null = null;
+    private firedIndicators: string[] = [];
+    private readonly cancellation = new AbortController();
+    private capturedUser: TikTokUser | null = null;
+    private reinjectTimer: ReturnType<typeof setTimeout> | null = null;
+    private exchangeTimer: AuthTimer | null = null;
+
+    constructor(
+        authUrl: string,
+        cookiesPath: string,
+        codeVerifier: string,
+        emit: AuthEventEmitter = () => {},
+        endpoints: AuthEndpoints = DEFAULT_AUTH_ENDPOINTS,
+        polling: PollingOptions = DEFAULT_AUTH_POLLING,
+        indicators: LoginIndicators = DEFAULT_LOGIN_INDICATORS
+    ) {
         this.authUrl = authUrl;
         this.cookiesPath = cookiesPath;
         this.codeVerifier = codeVerifier;
+        this.emit = emit;
+        this.endpoints = endpoints;
+        this.polling = polling;
+        this.indicators = indicators;
+    }
+
+    private emitProgress(status: TokenExchangeStatus) {
+        this.exchangeSpan?.record('status', status);
+        this.emit(IPC_CHANNELS.TOKEN_EXCHANGE_PROGRESS, { status });
+    }
+
+    public isWindowOpen(): boolean {
+        return this.window !== null;
     }
 
     public async findToken(): Promise<any> {
+        this.loginSpan = startSpan('login_flow', { navigations: 0 });
         return new Promise((resolve, reject) => {
             this.resolveToken = resolve;
             this.rejectToken = reject;
@@ -43,6 +226,13 @@ export class StreamlabsAuth {
         });
     }
 
+    private traceNavigation(url: string) {
+        this.navigations += 1;
+        this.loginSpan
+            ?.record('url_type', classifyUrl(url).kind)
+            .record('navigations', this.navigations);
+    }
+
     private async createWindow() {
         const preloadPath = require('path').join(getAppBasePath(), PATHS.PRELOAD_AUTH);
         console.log(CONSOLE_MESSAGES.ELECTRON_PRELOAD(preloadPath));
@@ -60,13 +250,14 @@ export class StreamlabsAuth {
             },
         });
 
+        this.loginSession = new LoginSession(this.window);
         this.setupIPC();
         this.setupLifecycle();
 
         await this.loadCookies();
 
         console.log(CONSOLE_MESSAGES.ELECTRON_NAVIGATE);
-        await this.window.loadURL(API_ENDPOINTS.TIKTOK_LOGIN);
+        await this.window.loadURL(this.endpoints.tiktokLoginUrl);
 
         this.checkLoginStatus(this.window.webContents.getURL());
     }
@@ -84,13 +275,8 @@ export class StreamlabsAuth {
             this.handleFetchResult(result);
         };
 
-        ipcMain.on(IPC_CHANNELS.LOG_CONSOLE, logHandler);
-        ipcMain.on(IPC_CHANNELS.FETCH_RESULT, resultHandler);
-
-        this.window?.on('closed', () => {
-            ipcMain.removeListener(IPC_CHANNELS.LOG_CONSOLE, logHandler);
-            ipcMain.removeListener(IPC_CHANNELS.FETCH_RESULT, resultHandler);
-        });
+        this.loginSession?.listen(IPC_CHANNELS.LOG_CONSOLE, logHandler);
+        this.loginSession?.listen(IPC_CHANNELS.FETCH_RESULT, resultHandler);
     }
 
     private setupLifecycle() {
@@ -98,88 +284,185 @@ export class StreamlabsAuth {
 
         const webContents = this.window.webContents as WebContents & { on: (event: string, callback: (...args: any[]) => void) => void };
         webContents.on('did-navigate', (_: any, url: string) => {
-            this.checkLoginStatus(url);
-            this.checkSuccess(url);
+            this.handleNavigation(url, true);
         });
 
         webContents.on('did-navigate-in-page', (_: any, url: string) => {
-            this.checkSuccess(url);
+            this.handleNavigation(url, false);
         });
 
         webContents.on('did-finish-load', () => {
             this.injectManualAuthButton();
+            this.detectLoginIndicators();
+            this.captureUserInfo();
         });
 
         this.window.on('closed', () => {
+            this.loginSession?.dispose();
             this.window = null;
+            this.loginSpan?.record('outcome', this.tokenFetchStarted ? 'completed' : 'window_closed').end();
             if (!this.tokenFetchStarted) {
-                this.rejectToken?.(new Error(ERROR_MESSAGES.WINDOW_CLOSED));
+                this.rejectToken?.(new AuthError('window_closed', ERROR_MESSAGES.WINDOW_CLOSED));
             }
         });
     }
 
-    private async loadCookies() {
-        if (FileUtils.exists(this.cookiesPath)) {
-            const cookies = FileUtils.readJsonArray(this.cookiesPath, []);
-            const promises = cookies.map((cookie: any) => {
-                const scheme = cookie.secure ? 'https' : 'http';
-                const domain = cookie.domain.startsWith('.') ? cookie.domain.substring(1) : cookie.domain;
-                const url = `${scheme}://${domain}${cookie.path}`;
-                return session.defaultSession.cookies.set({ ...cookie, url });
-            });
-            await Promise.all(promises);
-            console.log(CONSOLE_MESSAGES.ELECTRON_COOKIES_LOADED);
+    private handleNavigation(url: string, fullLoad: boolean) {
+        if (this.isDuplicateNavigation(url)) return;
+        if (fullLoad) {
+            this.traceNavigation(url);
+            this.checkLoginStatus(url);
+        } else {
+            this.scheduleReinjection();
         }
+        this.checkSuccess(url);
+    }
+
+    /**
+     * In-page route changes do not fire did-finish-load, and the SPA may re-render
+     * away what was injected. Re-inject once per burst of navigations; the scripts
+     * themselves skip work that is already done.
+     */
+    private scheduleReinjection() {
+        if (this.reinjectTimer) return;
+        this.reinjectTimer = setTimeout(() => {
+            this.reinjectTimer = null;
+            if (!this.window) return;
+            this.injectManualAuthButton();
+            this.captureUserInfo();
+        }, AUTH_CONFIG.REINJECT_DELAY_MS);
+    }
+
+    /**
+     * Both navigation events can fire for the same URL; skip identical
+     * consecutive URLs so redirects are not scheduled twice
+     */
+    private isDuplicateNavigation(url: string, now: number = Date.now()): boolean {
+        const last = this.lastNavigation;
+        this.lastNavigation = { url, at: now };
+        return last !== null && last.url === url && now - last.at < AUTH_CONFIG.NAVIGATION_DEDUP_MS;
+    }
+
+    private async loadCookies() {
+        const cookies = loadSessionCookies(this.cookiesPath);
+        if (cookies.length === 0) return;
+
+        const promises = cookies.map(cookie =>
+            session.defaultSession.cookies.set(toCookieSetDetails(cookie))
+                .catch(() => console.warn(CONSOLE_MESSAGES.ELECTRON_COOKIE_SET_FAIL(cookie.name)))
+        );
+        await Promise.all(promises);
+        console.log(CONSOLE_MESSAGES.ELECTRON_COOKIES_LOADED);
     }
 
     private async saveCookies() {
         const cookies = await session.defaultSession.cookies.get({});
         FileUtils.writeJson(this.cookiesPath, cookies);
+        captureStats.record(cookies.length, hasTikTokSession(cookies as StoredCookie[]));
     }
 
     private checkLoginStatus(url: string) {
-        if ((url.includes('tiktok.com') && !url.includes('login') && !url.includes('streamlabs')) || url.includes('/foryou')) {
-            console.log(CONSOLE_MESSAGES.ELECTRON_LOGIN_DETECTED);
-
-            setTimeout(() => {
-                const current = this.window?.webContents.getURL();
-                if (current && !current.includes('streamlabs')) {
-                    this.forceNavigateAuth();
-                }
-            }, 2000);
+        if (classifyUrl(url).kind === 'tiktok_logged_in') {
+            this.onTikTokLoggedIn(['url_pattern']);
         }
     }
 
+    /**
+     * Catch logins the URL heuristic misses (e.g. TikTok staying on /login after success)
+     */
+    private detectLoginIndicators() {
+        const url = this.window?.webContents.getURL() ?? '';
+        if (!url.includes('tiktok.com') || classifyUrl(url).kind === 'tiktok_logged_in') return;
+
+        this.window?.webContents.executeJavaScript(buildLoginCheckScript(this.indicators))
+            .then((result: unknown) => {
+                const fired = parseLoginIndicators(result);
+                if (fired.length > 0) this.onTikTokLoggedIn(fired);
+            })
+            .catch(() => { });
+    }
+
+    /**
+     * Read TikTok's `user_info` localStorage entry; it is per-origin, so only on TikTok pages
+     */
+    private captureUserInfo() {
+        const url = this.window?.webContents.getURL() ?? '';
+        if (!url.includes('tiktok.com')) return;
+
+        this.window?.webContents.executeJavaScript(`(function() {
+            try { return localStorage.getItem('user_info'); } catch (e) { return null; }
+        })()`)
+            .then((value: unknown) => {
+                const user = parseTikTokUserInfo(value);
+                if (!user) return;
+                this.capturedUser = user;
+                // Kept next to cookies.json so a saved account can be labelled later
+                FileUtils.writeJson(path.join(path.dirname(this.cookiesPath), PATHS.USER_INFO), { ...user });
+            })
+            .catch(() => { });
+    }
+
+    /**
+     * Timings of the token exchange, once one has started
+     */
+    getExchangeTimer(): AuthTimer | null {
+        return this.exchangeTimer;
+    }
+
+    getCapturedUser(): TikTokUser | null {
+        return this.capturedUser;
+    }
+
+    /**
+     * Indicators that made the last login detection fire, for diagnostics
+     */
+    public getLoginIndicators(): string[] {
+        return [...this.firedIndicators];
+    }
+
+    private onTikTokLoggedIn(indicators: string[]) {
+        console.log(CONSOLE_MESSAGES.ELECTRON_LOGIN_DETECTED);
+        this.firedIndicators = indicators;
+
+        setTimeout(() => {
+            const current = this.window?.webContents.getURL();
+            if (current && !current.includes('streamlabs')) {
+                this.forceNavigateAuth();
+            }
+        }, 2000);
+    }
+
     private forceNavigateAuth() {
         console.log(CONSOLE_MESSAGES.ELECTRON_FORCE_NAVIGATE(this.authUrl));
         this.window?.loadURL(this.authUrl).catch((e: any) => console.error('Failed to load Auth URL:', e));
     }
 
     private checkSuccess(url: string) {
-        let code: string | null = null;
-        try {
-            const urlObj = new URL(url);
-            code = urlObj.searchParams.get('code');
-        } catch (e) {
-            return;
-        }
+        const urlType = classifyUrl(url);
+        if (urlType.kind !== 'auth_success') return;
+        const code = urlType.code;
 
-        const isSuccess = (url.includes('success=true') && code) ||
-            (url.includes(API_ENDPOINTS.DASHBOARD) && code) ||
-            (url.includes(API_ENDPOINTS.SLOBS_DASHBOARD) && code);
-
-        if (isSuccess && !this.tokenFetchStarted && code) {
-            this.tokenFetchStarted = true;
+        if (this.beginTokenExchange()) {
             console.log(CONSOLE_MESSAGES.ELECTRON_SUCCESS(url));
             console.log(CONSOLE_MESSAGES.ELECTRON_CODE(code));
             console.log(CONSOLE_MESSAGES.ELECTRON_FETCH_START);
 
             this.saveCookies().then(() => {
-                this.executeTokenFetch(code!);
+                this.executeTokenFetch(code);
             });
         }
     }
 
+    /**
+     * Claim the single token exchange slot. Both navigation events can report the
+     * same success URL, so only the first caller may start the exchange.
+     */
+    private beginTokenExchange(): boolean {
+        if (this.tokenFetchStarted) return false;
+        this.tokenFetchStarted = true;
+        return true;
+    }
+
     private injectManualAuthButton() {
         const script = `
         (function() {
@@ -197,10 +480,22 @@ export class StreamlabsAuth {
         this.window?.webContents.executeJavaScript(script).catch(() => { });
     }
 
+    /**
+     * Inject a debugging script into the open login window (see validateCustomScript)
+     */
+    public async injectCustomScript(label: string, script: string): Promise<void> {
+        validateCustomScript(script);
+        if (!this.window) {
+            throw new Error(ERROR_MESSAGES.NO_LOGIN_WINDOW);
+        }
+        await this.window.webContents.executeJavaScript(script);
+        console.log(CONSOLE_MESSAGES.CUSTOM_SCRIPT_INJECTED(label));
+    }
+
     private async executeTokenFetch(code: string) {
         if (!this.codeVerifier) {
             console.error(CONSOLE_MESSAGES.ELECTRON_NO_VERIFIER);
-            this.rejectToken?.(new Error(ERROR_MESSAGES.NO_CODE_VERIFIER));
+            this.rejectToken?.(new AuthError('missing_verifier', ERROR_MESSAGES.NO_CODE_VERIFIER));
             return;
         }
 
@@ -209,7 +504,7 @@ export class StreamlabsAuth {
         const fetchCode = `
         (async () => {
             try {
-                const res = await fetch('${API_ENDPOINTS.AUTH_DATA}?code=${code}&code_verifier=${this.codeVerifier}', {
+                const res = await fetch('${this.endpoints.authDataUrl}?code=${code}&code_verifier=${this.codeVerifier}', {
                     method: 'GET',
                     credentials: 'include',
                     headers: { 
@@ -218,6 +513,9 @@ export class StreamlabsAuth {
                     }
                 });
                 const text = await res.text();
+                if (new TextEncoder().encode(text).length > ${AUTH_CONFIG.MAX_PAYLOAD_BYTES}) {
+                    return { success: false, error: '${ERROR_MESSAGES.PAYLOAD_TOO_LARGE(AUTH_CONFIG.MAX_PAYLOAD_BYTES)}', status: res.status };
+                }
                 try {
                     const json = JSON.parse(text);
                     return { success: true, data: json, status: res.status };
@@ -230,35 +528,94 @@ export class StreamlabsAuth {
         })()
         `;
 
+        this.exchangeSpan = startSpan('token_exchange');
+        this.exchangeTimer = new AuthTimer();
+        let outcome: RetryOutcome<AuthResult>;
         try {
-            const result = await this.window?.webContents.executeJavaScript(fetchCode);
-            this.handleFetchResult(result);
+            outcome = await retry(this.polling, () => this.runTokenFetch(fetchCode), {
+                // success:false on a parsed response means the token is not provisioned yet
+                retryOnResult: result => result.success && result.data?.success === false,
+                signal: this.cancellation.signal,
+            });
+        } catch {
+            // Only cancel() aborts the polling, and it has already settled the login
+            this.exchangeSpan.record('outcome', 'cancelled').end();
+            return;
+        }
+        this.exchangeSpan.record('attempts', outcome.attempts);
+        this.handleFetchResult(outcome.result);
+    }
+
+    /**
+     * Abort the login: stop token exchange polling, close the window, drop its
+     * listeners and reject findToken() with a 'cancelled' AuthError
+     */
+    public cancel(): void {
+        if (this.cancellation.signal.aborted) return;
+        if (this.reinjectTimer) clearTimeout(this.reinjectTimer);
+        const error = new AuthError('cancelled', ERROR_MESSAGES.LOGIN_CANCELLED);
+        this.cancellation.abort(error);
+        this.loginSpan?.record('outcome', 'cancelled').end();
+        this.rejectToken?.(error);
+        if (this.loginSession) {
+            this.loginSession.dispose();
+        } else {
+            this.window?.close();
+        }
+        this.window = null;
+    }
+
+    private async runTokenFetch(fetchCode: string): Promise<AuthResult> {
+        // The user may close the window while the exchange is still polling
+        const window = this.window;
+        if (!window) {
+            return { success: false, error: ERROR_MESSAGES.WINDOW_CLOSED };
+        }
+        try {
+            this.emitProgress('request_sent');
+            const timer = this.exchangeTimer ??= new AuthTimer();
+            const result = await timer.time(() => window.webContents.executeJavaScript(fetchCode));
+            this.emitProgress('response_received');
+            return result;
         } catch (err: any) {
             console.error(CONSOLE_MESSAGES.ELECTRON_JS_ERROR(err.message));
-            this.handleFetchResult({ success: false, error: err.message });
+            // Closing the window while the script runs rejects it; that is not an HTTP failure
+            if (this.window !== window || window.isDestroyed()) {
+                return { success: false, error: ERROR_MESSAGES.WINDOW_CLOSED };
+            }
+            return { success: false, error: err.message };
         }
     }
 
     private handleFetchResult(result: AuthResult) {
         console.log(CONSOLE_MESSAGES.ELECTRON_RESULT(JSON.stringify(result)));
 
-        if (result.success && result.data?.success) {
-            const authData = result.data.data;
+        const authData = result.success ? extractAuthData(result.data) : null;
+        this.exchangeSpan?.record('http_status', result.status ?? 0);
+        if (authData) {
             console.log(CONSOLE_MESSAGES.ELECTRON_AUTH_SUCCESS);
+            this.emitProgress('token_extracted');
+            this.exchangeSpan?.record('outcome', 'success').end();
+            this.emit(IPC_CHANNELS.TOKEN_EXCHANGE_COMPLETE, { success: true });
             this.resolveToken?.(authData);
             this.cleanup();
         } else {
             console.error(CONSOLE_MESSAGES.ELECTRON_ERROR_RESULT(JSON.stringify(result)));
-            this.rejectToken?.(new Error(`${ERROR_MESSAGES.FETCH_FAILED}: ${JSON.stringify(result)}`));
+            this.exchangeSpan?.record('outcome', 'failed').end();
+            this.emit(IPC_CHANNELS.TOKEN_EXCHANGE_FAILED, { success: false, error: result.error ?? ERROR_MESSAGES.FETCH_FAILED });
+            this.rejectToken?.(new AuthError(failureKind(result), `${ERROR_MESSAGES.FETCH_FAILED}: ${JSON.stringify(result)}`, result.status));
             this.cleanup();
         }
     }
 
     private async cleanup() {
+        if (this.reinjectTimer) clearTimeout(this.reinjectTimer);
         await this.saveCookies();
-        if (this.window) {
-            this.window.close();
-            this.window = null;
+        if (this.loginSession) {
+            this.loginSession.dispose();
+        } else {
+            this.window?.close();
         }
+        this.window = null;
     }
 }
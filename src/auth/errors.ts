@@ -0,0 +1,24 @@
+export type AuthErrorKind =
+    | 'window_closed'
+    | 'timeout'
+    | 'http'
+    | 'parse'
+    | 'token_missing'
+    | 'missing_verifier'
+    | 'invalid_code'
+    | 'cancelled';
+
+/**
+ * Error raised by the login flow and token exchange so callers (and the UI,
+ * via the IPC `kind` field) can tell failures apart
+ */
+export class AuthError extends Error {
+    constructor(
+        public readonly kind: AuthErrorKind,
+        message: string,
+        public readonly status?: number
+    ) {
+        super(message);
+        this.name = 'AuthError';
+    }
+}
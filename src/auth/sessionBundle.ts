@@ -0,0 +1,83 @@
+import { app } from 'electron';
+import { PATHS, ERROR_MESSAGES } from '../constants';
+import { FileUtils, TokenStorage, extractOauthToken } from '../utils/fileUtils';
+import { loadStoredCookies, parseJsonCookies, type StoredCookie } from './cookies';
+import { safeSlice } from '../utils/strUtils';
+
+export const SESSION_BUNDLE_VERSION = 1;
+
+/**
+ * Portable snapshot of the captured login state, for support and debugging
+ */
+export interface SessionBundle {
+    version: number;
+    appVersion: string;
+    exportedAt: string;
+    redacted: boolean;
+    cookies: StoredCookie[];
+    token: {
+        present: boolean;
+        expires_at?: string;
+        data?: Record<string, unknown>;
+    };
+}
+
+const MASK_SUFFIX = '***';
+
+/**
+ * Keep just enough of a secret to tell values apart
+ */
+export function maskSecret(value: string): string {
+    return `${safeSlice(value, 0, 4)}${MASK_SUFFIX}`;
+}
+
+export function isMasked(value: string): boolean {
+    return value.endsWith(MASK_SUFFIX);
+}
+
+/**
+ * Combine cookies and token metadata into one JSON object.
+ * With `redact`, cookie values are masked and the raw token is left out.
+ */
+export function exportSessionBundle(options: { redact: boolean; appVersion?: string }): SessionBundle {
+    const cookies = loadStoredCookies();
+    const tokenData = new TokenStorage(PATHS.TOKENS).load() ?? {};
+    const present = Object.keys(tokenData).length > 0;
+    const expiresAt = typeof tokenData.expires_at === 'string' ? tokenData.expires_at : undefined;
+
+    return {
+        version: SESSION_BUNDLE_VERSION,
+        appVersion: options.appVersion ?? app.getVersion(),
+        exportedAt: new Date().toISOString(),
+        redacted: options.redact,
+        cookies: options.redact ? cookies.map(c => ({ ...c, value: maskSecret(c.value) })) : cookies,
+        token: {
+            present,
+            ...(expiresAt && { expires_at: expiresAt }),
+            ...(present && !options.redact && { data: tokenData }),
+        },
+    };
+}
+
+/**
+ * Restore cookies (and the token, when present) from an unredacted bundle
+ */
+export function importSessionBundle(bundle: unknown): { cookieCount: number; tokenImported: boolean } {
+    const data = bundle as Partial<SessionBundle> | null;
+    if (!data || data.version !== SESSION_BUNDLE_VERSION || !Array.isArray(data.cookies)) {
+        throw new Error(ERROR_MESSAGES.INVALID_SESSION_BUNDLE);
+    }
+    const cookies = parseJsonCookies(JSON.stringify(data.cookies));
+    if (data.redacted || cookies.some(c => isMasked(c.value))) {
+        throw new Error(ERROR_MESSAGES.REDACTED_SESSION_BUNDLE);
+    }
+
+    FileUtils.writeJson(PATHS.COOKIES, cookies);
+
+    const tokenData = data.token?.data;
+    const tokenImported = Boolean(tokenData && extractOauthToken(tokenData));
+    if (tokenData && tokenImported) {
+        new TokenStorage(PATHS.TOKENS).save(tokenData);
+    }
+    return { cookieCount: cookies.length, tokenImported };
+}
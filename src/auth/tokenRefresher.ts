@@ -0,0 +1,109 @@
+import { API_ENDPOINTS, AUTH_CONFIG, CONSOLE_MESSAGES, ERROR_MESSAGES, HTTP_CONFIG, PATHS } from '../constants';
+import { TokenStorage } from '../utils/fileUtils';
+import { buildHeaders } from '../utils/http';
+import { AuthError } from './errors';
+import { parseTokenResponse } from './tokenResponse';
+
+// setTimeout fires immediately for delays above 2^31-1 ms (~24.8 days)
+const MAX_TIMER_DELAY_MS = 2 ** 31 - 1;
+
+export interface TokenRefresherOptions {
+    onRefreshed: (token: string) => void;
+    onError?: (error: Error) => void;
+    refreshUrl?: string;
+    leadTimeMs?: number;
+    minDelayMs?: number;
+}
+
+/**
+ * Renews the Streamlabs token in the background shortly before its `expires_at`,
+ * using the `refresh_token` saved in tokens.json
+ */
+export class TokenRefresher {
+    private timer: ReturnType<typeof setTimeout> | null = null;
+    private readonly refreshUrl: string;
+    private readonly leadTimeMs: number;
+    private readonly minDelayMs: number;
+
+    constructor(private readonly options: TokenRefresherOptions) {
+        this.refreshUrl = options.refreshUrl ?? API_ENDPOINTS.TOKEN_REFRESH;
+        this.leadTimeMs = options.leadTimeMs ?? AUTH_CONFIG.TOKEN_REFRESH_LEAD_MS;
+        this.minDelayMs = options.minDelayMs ?? AUTH_CONFIG.TOKEN_REFRESH_MIN_DELAY_MS;
+    }
+
+    /**
+     * (Re)schedule the refresh from tokens.json. Returns false, leaving the
+     * refresher stopped, when the token has no expiry or no refresh token.
+     */
+    start(): boolean {
+        this.stop();
+        const storage = new TokenStorage(PATHS.TOKENS);
+        const expiresAt = storage.getExpiresAt();
+        const refreshToken = storage.getRefreshToken();
+        if (!expiresAt || !refreshToken) return false;
+
+        // The floor keeps a token that lives shorter than the lead time from
+        // being refreshed back to back
+        const delay = Math.max(this.minDelayMs, expiresAt.getTime() - this.leadTimeMs - Date.now());
+        const refreshAt = Date.now() + delay;
+        if (delay > MAX_TIMER_DELAY_MS) {
+            this.timer = setTimeout(() => this.start(), MAX_TIMER_DELAY_MS);
+            return true;
+        }
+
+        console.log(CONSOLE_MESSAGES.TOKEN_REFRESH_SCHEDULED(new Date(refreshAt).toISOString()));
+        this.timer = setTimeout(() => {
+            this.timer = null;
+            this.refresh(refreshToken).catch(error => {
+                console.error(CONSOLE_MESSAGES.TOKEN_REFRESH_FAILED, error?.message);
+                this.options.onError?.(error);
+            });
+        }, delay);
+        return true;
+    }
+
+    /**
+     * Cancel the scheduled refresh (e.g. on logout)
+     */
+    stop(): void {
+        if (this.timer) clearTimeout(this.timer);
+        this.timer = null;
+    }
+
+    isRunning(): boolean {
+        return this.timer !== null;
+    }
+
+    /**
+     * Exchange the refresh token, save the new token and schedule the next refresh
+     */
+    async refresh(refreshToken: string): Promise<string> {
+        let response: Response;
+        try {
+            response = await fetch(this.refreshUrl, {
+                method: 'POST',
+                headers: { ...buildHeaders(), 'Accept': 'application/json' },
+                body: new URLSearchParams({ grant_type: 'refresh_token', refresh_token: refreshToken }),
+                signal: AbortSignal.timeout(HTTP_CONFIG.TIMEOUT_MS),
+            });
+        } catch (error) {
+            const timedOut = error instanceof Error && error.name === 'TimeoutError';
+            throw new AuthError(timedOut ? 'timeout' : 'http', `${ERROR_MESSAGES.FETCH_FAILED}: ${(error as Error)?.message}`);
+        }
+        if (!response.ok) {
+            throw new AuthError('http', `${ERROR_MESSAGES.FETCH_FAILED}: HTTP ${response.status}`, response.status);
+        }
+
+        const body = await response.json().catch(() => {
+            throw new AuthError('parse', ERROR_MESSAGES.JSON_PARSE_ERROR);
+        });
+        const { oauthToken, data } = parseTokenResponse(body);
+        // Keep the current refresh token unless the server rotated it
+        new TokenStorage(PATHS.TOKENS).save({ refresh_token: refreshToken, ...data });
+        console.log(CONSOLE_MESSAGES.TOKEN_REFRESHED);
+
+        this.start();
+        this.options.onRefreshed(oauthToken);
+        return oauthToken;
+    }
+}
@@ -0,0 +1,58 @@
+import { ERROR_MESSAGES } from '../constants';
+import { AuthError } from './errors';
+
+/**
+ * Token response from /auth/data, in either of its known shapes:
+ * flat `{ oauth_token }` or nested `{ success, data: { oauth_token | access_token } }`
+ */
+export interface TokenResponse {
+    oauthToken: string;
+    /** The token-bearing object, as persisted to tokens.json */
+    data: Record<string, unknown>;
+}
+
+const isObject = (value: unknown): value is Record<string, unknown> =>
+    typeof value === 'object' && value !== null && !Array.isArray(value);
+
+/**
+ * Parse a token response, failing with the exact field that is missing
+ * instead of a generic "no token"
+ */
+export function parseTokenResponse(payload: unknown): TokenResponse {
+    if (!isObject(payload)) {
+        const actual = payload === null ? 'null' : Array.isArray(payload) ? 'array' : typeof payload;
+        throw new AuthError('parse', ERROR_MESSAGES.INVALID_TOKEN_RESPONSE(`expected a JSON object, got ${actual}`));
+    }
+    if (payload.success === false) {
+        throw new AuthError('token_missing', ERROR_MESSAGES.INVALID_TOKEN_RESPONSE('success is false'));
+    }
+
+    const nested = isObject(payload.data) ? payload.data : null;
+    // Each field paired with the object it lives in, which is what gets persisted
+    const candidates: Array<[string, unknown, Record<string, unknown> | null]> = [
+        ['oauth_token', payload.oauth_token, payload],
+        ['data.oauth_token', nested?.oauth_token, nested],
+        ['data.access_token', nested?.access_token, nested],
+    ];
+    const found = candidates.find(([, value]) => typeof value === 'string' && value.length > 0);
+    if (!found) {
+        const keys = [...Object.keys(payload), ...Object.keys(nested ?? {}).map(key => `data.${key}`)];
+        throw new AuthError('token_missing', ERROR_MESSAGES.INVALID_TOKEN_RESPONSE(
+            `missing ${candidates.map(([field]) => field).join(' / ')} (found: ${keys.join(', ') || 'nothing'})`
+        ));
+    }
+
+    return { oauthToken: found[1] as string, data: found[2] ?? payload };
+}
+
+/**
+ * parseTokenResponse for callers that only need to know whether a token is
+ * there (polling, stored files); returns null instead of throwing
+ */
+export function tryParseTokenResponse(payload: unknown): TokenResponse | null {
+    try {
+        return parseTokenResponse(payload);
+    } catch {
+        return null;
+    }
+}
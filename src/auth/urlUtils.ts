@@ -0,0 +1,84 @@
+import { API_ENDPOINTS } from '../constants';
+
+/**
+ * Classification of a URL seen by the login window.
+ * Plain JSON data, so it can be sent over IPC, logged and compared with toEqual.
+ */
+export type UrlType =
+    | { kind: 'auth_success'; code: string }
+    | { kind: 'tiktok_logged_in' }
+    | { kind: 'other' };
+
+/**
+ * Tracking params stripped by canonicalize(); a trailing `*` matches a prefix
+ */
+export const DEFAULT_TRACKING_PARAMS = ['utm_*', 'lang', 'enter_from', 'enter_method', 'is_from_webapp', 'sender_device'];
+
+const PRESERVED_PARAMS = new Set(['code', 'state', 'error']);
+
+/**
+ * Remove tracking query params from a URL, always keeping `code`/`state`/`error`.
+ * Unparseable input is returned unchanged.
+ */
+export function canonicalize(url: string, trackingParams: string[] = DEFAULT_TRACKING_PARAMS): string {
+    let parsed: URL;
+    try {
+        parsed = new URL(url);
+    } catch {
+        return url;
+    }
+
+    const isTracking = (name: string) => trackingParams.some(pattern =>
+        pattern.endsWith('*') ? name.startsWith(pattern.slice(0, -1)) : name === pattern
+    );
+
+    for (const name of [...parsed.searchParams.keys()]) {
+        if (!PRESERVED_PARAMS.has(name) && isTracking(name)) {
+            parsed.searchParams.delete(name);
+        }
+    }
+    return parsed.toString();
+}
+
+/**
+ * Extract the OAuth `code` query parameter from a URL.
+ * Returns null for unparseable URLs or an empty/missing code.
+ */
+export function extractAuthCode(url: string): string | null {
+    try {
+        return new URL(url).searchParams.get('code') || null;
+    } catch {
+        return null;
+    }
+}
+
+/**
+ * Whether a manually pasted code is non-empty and made of RFC 3986 unreserved characters
+ */
+export function isValidAuthCode(code: string): boolean {
+    return /^[A-Za-z0-9._~-]+$/.test(code);
+}
+
+/**
+ * Decide what a navigated URL means for the login flow.
+ * With `canonical`, tracking params are stripped first.
+ */
+export function classifyUrl(url: string, options: { canonical?: boolean } = {}): UrlType {
+    if (options.canonical) {
+        url = canonicalize(url);
+    }
+    const code = extractAuthCode(url);
+    const isSuccess = url.includes('success=true') ||
+        url.includes(API_ENDPOINTS.DASHBOARD) ||
+        url.includes(API_ENDPOINTS.SLOBS_DASHBOARD);
+
+    if (code && isSuccess) {
+        return { kind: 'auth_success', code };
+    }
+
+    if ((url.includes('tiktok.com') && !url.includes('login') && !url.includes('streamlabs')) || url.includes('/foryou')) {
+        return { kind: 'tiktok_logged_in' };
+    }
+
+    return { kind: 'other' };
+}
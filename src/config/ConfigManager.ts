@@ -8,42 +8,223 @@ export interface AppConfig {
     game?: string;
     audienceType?: string;
     suppressDonationReminder?: boolean;
+    watchConfig?: boolean;
+    /** Overrides the user-agent sent to the Streamlabs API */
+    userAgent?: string;
+    /** When false, tokens and cookies are kept in memory only and never written to disk */
+    persistSecrets?: boolean;
+    /** When false, starting a stream does not write its title/game back to config.json */
+    rememberStreamInfo?: boolean;
+    /** Exposes the login-window script injection command in packaged builds (debugging only) */
+    debugScriptInjection?: boolean;
+    /** Renew the token in the background via the (unverified) refresh endpoint */
+    tokenRefresh?: boolean;
+}
+
+const DEFAULT_CONFIG: AppConfig = {
+    audienceType: '0',
+    suppressDonationReminder: false
+};
+
+/**
+ * Environment variables that override config.json values.
+ * They take highest precedence and are never written back to disk.
+ */
+export const CONFIG_ENV_VARS = {
+    token: 'STREAM_TOKEN',
+    title: 'STREAM_TITLE',
+    game: 'STREAM_GAME',
+} as const;
+
+type ConfigFieldType = 'string' | 'boolean';
+
+const CONFIG_SCHEMA: Record<keyof AppConfig, ConfigFieldType> = {
+    token: 'string',
+    title: 'string',
+    game: 'string',
+    audienceType: 'string',
+    suppressDonationReminder: 'boolean',
+    watchConfig: 'boolean',
+    userAgent: 'string',
+    persistSecrets: 'boolean',
+    rememberStreamInfo: 'boolean',
+    debugScriptInjection: 'boolean',
+    tokenRefresh: 'boolean',
+};
+
+/** Fields the renderer may see; everything else (token, userAgent, ...) stays in the main process */
+const RENDERER_FIELDS = ['title', 'game', 'audienceType', 'suppressDonationReminder', 'rememberStreamInfo'] as const;
+
+export type RendererConfig = Pick<AppConfig, typeof RENDERER_FIELDS[number]>;
+
+/**
+ * The UI subset of the config, safe to send over IPC
+ */
+export function toRendererConfig(config: AppConfig): RendererConfig {
+    return Object.fromEntries(
+        RENDERER_FIELDS.filter(field => config[field] !== undefined).map(field => [field, config[field]])
+    ) as RendererConfig;
+}
+
+export interface ConfigFieldError {
+    field: string;
+    expected: string;
+    actual: string;
+}
+
+function describeType(value: unknown): string {
+    if (value === null) return 'null';
+    return Array.isArray(value) ? 'array' : typeof value;
+}
+
+/**
+ * Check parsed config.json data against the AppConfig field types.
+ * Invalid fields are dropped (so defaults apply) and reported; unknown keys are kept.
+ * Booleans may also be written as "true"/"false" strings; they are converted to
+ * real booleans so every consumer (including the renderer) sees one type.
+ */
+export function validateConfig(data: unknown): { config: Partial<AppConfig>; errors: ConfigFieldError[] } {
+    if (typeof data !== 'object' || data === null || Array.isArray(data)) {
+        return { config: {}, errors: [{ field: '$', expected: 'object', actual: describeType(data) }] };
+    }
+
+    const config: Record<string, unknown> = {};
+    const errors: ConfigFieldError[] = [];
+    for (const [field, value] of Object.entries(data)) {
+        const expected = CONFIG_SCHEMA[field as keyof AppConfig];
+        if (expected === 'boolean' && (value === 'true' || value === 'false')) {
+            config[field] = value === 'true';
+        } else if (!expected || typeof value === expected) {
+            config[field] = value;
+        } else {
+            errors.push({ field, expected, actual: describeType(value) });
+        }
+    }
+    return { config: config as Partial<AppConfig>, errors };
 }
 
 export class ConfigManager {
     private configPath: string;
     private config: AppConfig;
+    private errors: ConfigFieldError[] = [];
 
     constructor(filename: string = 'config.json') {
         this.configPath = resolveAppPath(filename);
-        this.config = {
-            audienceType: '0',
-            suppressDonationReminder: false
-        };
+        this.config = { ...DEFAULT_CONFIG };
     }
 
     load(): AppConfig {
-        if (fs.existsSync(this.configPath)) {
-            try {
-                const data = fs.readFileSync(this.configPath, 'utf-8');
-                this.config = { ...this.config, ...JSON.parse(data) };
-            } catch (error) {
-                console.error('Error loading config:', error);
-            }
-        }
+        this.config = { ...DEFAULT_CONFIG, ...this.readFile(), ...this.readEnv() };
         return this.config;
     }
 
     save(newConfig: Partial<AppConfig>): void {
-        this.config = { ...this.config, ...newConfig };
+        // New values win; undefined ones fall back to what is already stored
+        const updates = Object.fromEntries(
+            Object.entries(newConfig).filter(([, value]) => value !== undefined)
+        ) as Partial<AppConfig>;
+        // Re-read the file so values written by another instance (e.g. the token) survive
+        const persisted = { ...DEFAULT_CONFIG, ...this.readFile(), ...updates };
         try {
-            fs.writeFileSync(this.configPath, JSON.stringify(this.config, null, 2));
+            fs.writeFileSync(this.configPath, JSON.stringify(persisted, null, 2));
         } catch (error) {
             console.error('Error saving config:', error);
         }
+        this.config = { ...persisted, ...this.readEnv() };
     }
 
-    get(key: keyof AppConfig): any {
+    get<K extends keyof AppConfig>(key: K): AppConfig[K] {
         return this.config[key];
     }
+
+    /**
+     * Read a boolean setting; undefined when unset or not a boolean field
+     */
+    getBool(key: keyof AppConfig): boolean | undefined {
+        const value: unknown = this.config[key];
+        return typeof value === 'boolean' ? value : undefined;
+    }
+
+    getAll(): AppConfig {
+        return { ...this.config };
+    }
+
+    /**
+     * Field errors found in config.json by the last load/save
+     */
+    getValidationErrors(): ConfigFieldError[] {
+        return [...this.errors];
+    }
+
+    /**
+     * Reload the config whenever config.json changes on disk.
+     * Rapid writes are debounced into a single reload. Returns a function that stops watching.
+     */
+    watch(onChange: (config: AppConfig) => void, debounceMs: number = 300): () => void {
+        const handler = this.createChangeHandler(onChange, debounceMs);
+        // Watch the directory so editors that replace the file are still picked up
+        const watcher = fs.watch(path.dirname(this.configPath), (_, changed) => handler.onEvent(changed?.toString() ?? null));
+
+        return () => {
+            handler.cancel();
+            watcher.close();
+        };
+    }
+
+    /**
+     * The debounced reload behind watch(), fed with the file name of each
+     * directory event (null when the platform does not report it)
+     */
+    createChangeHandler(onChange: (config: AppConfig) => void, debounceMs: number): {
+        onEvent: (changed: string | null) => void;
+        cancel: () => void;
+    } {
+        let timer: ReturnType<typeof setTimeout> | null = null;
+        const filename = path.basename(this.configPath);
+        const cancel = () => {
+            if (timer) clearTimeout(timer);
+            timer = null;
+        };
+
+        return {
+            onEvent: changed => {
+                if (changed && changed !== filename) return;
+                cancel();
+                timer = setTimeout(() => {
+                    timer = null;
+                    onChange(this.load());
+                }, debounceMs);
+            },
+            cancel,
+        };
+    }
+
+    private readFile(): Partial<AppConfig> {
+        this.errors = [];
+        if (fs.existsSync(this.configPath)) {
+            try {
+                const data = fs.readFileSync(this.configPath, 'utf-8');
+                const { config, errors } = validateConfig(JSON.parse(data));
+                for (const error of errors) {
+                    console.warn(`[Config] Ignoring "${error.field}": expected ${error.expected}, got ${error.actual}`);
+                }
+                this.errors = errors;
+                return config;
+            } catch (error) {
+                console.error('Error loading config:', error);
+            }
+        }
+        return {};
+    }
+
+    private readEnv(): Partial<AppConfig> {
+        const overrides: Partial<AppConfig> = {};
+        for (const [key, envVar] of Object.entries(CONFIG_ENV_VARS)) {
+            const value = process.env[envVar];
+            if (value !== undefined) {
+                overrides[key as keyof typeof CONFIG_ENV_VARS] = value;
+            }
+        }
+        return overrides;
+    }
 }
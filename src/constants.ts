@@ -7,17 +7,58 @@
 export const IPC_CHANNELS = {
     // Auth channels
     AUTH_LOGIN: 'auth:login',
+    AUTH_STATE: 'auth:state',
+    APP_STATUS: 'app:status',
+    APP_CONNECTIVITY: 'app:connectivity',
+    APP_VERSION_INFO: 'app:version-info',
+    AUTH_IMPORT_COOKIES: 'auth:import-cookies',
+    AUTH_CLEAR_TIKTOK_COOKIES: 'auth:clear-tiktok-cookies',
+    AUTH_VALIDATE_COOKIES: 'auth:validate-cookies',
+    AUTH_VERIFY_COOKIES: 'auth:verify-cookies',
+    AUTH_CAPTURED_USER: 'auth:captured-user',
+    AUTH_VALIDATE_TOKEN: 'auth:validate-token',
+    AUTH_INJECT_SCRIPT: 'auth:inject-script',
+    AUTH_CAPTURE_STATS: 'auth:capture-stats',
+    AUTH_LOGIN_INDICATORS: 'auth:login-indicators',
+    AUTH_CAPTURE_UPDATED: 'auth:capture-updated',
+    AUTH_EXPORT_SESSION: 'auth:export-session',
+    AUTH_IMPORT_SESSION: 'auth:import-session',
+    AUTH_SUBMIT_CODE: 'auth:submit-code',
+    AUTH_LIST_ACCOUNTS: 'auth:list-accounts',
+    AUTH_TOKEN_REFRESHED: 'auth:token-refreshed',
+    AUTH_TOKEN_REFRESH_FAILED: 'auth:token-refresh-failed',
+    AUTH_PREPARE_LOGIN: 'auth:prepare-login',
+    AUTH_CANCEL_LOGIN: 'auth:cancel-login',
+    AUTH_METRICS: 'auth:metrics',
+    TOKEN_EXCHANGE_PROGRESS: 'auth:token-exchange-progress',
+    TOKEN_EXCHANGE_COMPLETE: 'auth:token-exchange-complete',
+    TOKEN_EXCHANGE_FAILED: 'auth:token-exchange-failed',
     
     // Stream channels
     STREAM_INFO: 'stream:info',
     STREAM_SEARCH: 'stream:search',
     STREAM_START: 'stream:start',
     STREAM_END: 'stream:end',
+    STREAM_RESTART: 'stream:restart',
     STREAM_CURRENT: 'stream:current',
+    STREAM_INGEST: 'stream:ingest',
+    STREAM_HISTORY: 'stream:history',
+    STREAM_AUDIENCE_TYPES: 'stream:audience-types',
     
     // User channels
     USER_PROFILE: 'user:profile',
     
+    // Config channels
+    CONFIG_LAST_STREAM: 'config:last-stream',
+    CONFIG_CHANGED: 'config:changed',
+    CONFIG_GET_REMEMBER_STREAM_INFO: 'config:get-remember-stream-info',
+    CONFIG_SET_REMEMBER_STREAM_INFO: 'config:set-remember-stream-info',
+    
+    // Credential file channels
+    FILES_LIST: 'files:list',
+    FILES_DELETE: 'files:delete',
+    FILES_SET_DIR: 'files:set-dir',
+    
     // Electron-login channels
     LOG_CONSOLE: 'log-console',
     FETCH_RESULT: 'fetch-result',
@@ -30,13 +71,51 @@ export const API_BASE_URL = 'https://streamlabs.com/api/v5/slobs';
 export const API_ENDPOINTS = {
     TIKTOK_BASE: `${API_BASE_URL}/tiktok`,
     AUTH_DATA: `${API_BASE_URL}/auth/data`,
+    /**
+     * Not seen in captured traffic or scripts/discover.ts yet; assumed from the
+     * OAuth refresh_token grant, so it is only called with `tokenRefresh` enabled
+     */
+    TOKEN_REFRESH: `${API_BASE_URL}/auth/refresh`,
     TIKTOK_AUTH: 'https://streamlabs.com/tiktok/auth',
     LOGIN_URL: 'https://streamlabs.com/m/login',
     TIKTOK_LOGIN: 'https://www.tiktok.com/login',
+    TIKTOK_ACCOUNT_INFO: 'https://www.tiktok.com/passport/web/account/info/',
     DASHBOARD: 'https://streamlabs.com/dashboard',
     SLOBS_DASHBOARD: 'https://streamlabs.com/slobs/dashboard',
 } as const;
 
+/**
+ * Streamlabs/TikTok endpoints used by the login flow.
+ * Overridable so tests and staging can point at another server.
+ */
+export interface AuthEndpoints {
+    loginUrl: string;
+    authDataUrl: string;
+    tiktokLoginUrl: string;
+}
+
+export const DEFAULT_AUTH_ENDPOINTS: AuthEndpoints = {
+    loginUrl: API_ENDPOINTS.LOGIN_URL,
+    authDataUrl: API_ENDPOINTS.AUTH_DATA,
+    tiktokLoginUrl: API_ENDPOINTS.TIKTOK_LOGIN,
+};
+
+/**
+ * Retry schedule for /auth/data, which can briefly report success:false
+ * while Streamlabs finishes provisioning the token
+ */
+export interface PollingOptions {
+    maxAttempts: number;
+    initialDelayMs: number;
+    maxDelayMs: number;
+}
+
+export const DEFAULT_AUTH_POLLING: PollingOptions = {
+    maxAttempts: 5,
+    initialDelayMs: 500,
+    maxDelayMs: 4000,
+};
+
 // ============== Authentication Constants ==============
 export const AUTH_CONFIG = {
     CLIENT_KEY: 'awdjaq9ide8ofrtz',
@@ -44,6 +123,18 @@ export const AUTH_CONFIG = {
     FORCE_VERIFY: '1',
     EXTERNAL: 'mobile',
     SKIP_SPLASH: '1',
+    /** Size cap for debugging scripts injected into the login window */
+    CUSTOM_SCRIPT_MAX_BYTES: 64 * 1024,
+    /** Largest captured payload (cookie file, auth-data body) parsed as JSON */
+    MAX_PAYLOAD_BYTES: 1024 * 1024,
+    /** Identical consecutive navigations within this window are processed once */
+    NAVIGATION_DEDUP_MS: 1000,
+    /** Delay before re-injecting page scripts after an in-page (SPA) navigation, so the route can render */
+    REINJECT_DELAY_MS: 300,
+    /** How long before `expires_at` the background refresher renews the token */
+    TOKEN_REFRESH_LEAD_MS: 5 * 60 * 1000,
+    /** Floor between refreshes, for tokens that live shorter than the lead time */
+    TOKEN_REFRESH_MIN_DELAY_MS: 60 * 1000,
 } as const;
 
 // ============== Window Configuration ==============
@@ -68,20 +159,59 @@ export const PATHS = {
     INDEX_HTML: 'dist/ui/index.html',
     COOKIES: 'cookies.json',
     TOKENS: 'tokens.json',
+    /** TikTok `user_info` captured during login, so saved accounts can show who they belong to */
+    USER_INFO: 'user_info.json',
     CONFIG: 'config.json',
+    /** One subdirectory per saved account, each usable as a credential directory */
+    ACCOUNTS_DIR: 'accounts',
 } as const;
 
+// ============== TikTok Session ==============
+export const TIKTOK_SESSION_COOKIES = ['sessionid', 'sessionid_ss', 'sid_tt'] as const;
+
+/**
+ * DOM selectors and cookie names that signal a logged-in TikTok page.
+ * Kept as data so TikTok markup changes only need a list update.
+ */
+export interface LoginIndicators {
+    selectors: string[];
+    cookieNames: string[];
+}
+
+export const DEFAULT_LOGIN_INDICATORS: LoginIndicators = {
+    selectors: ['[data-e2e="user-avatar"]', '[data-e2e="profile-icon"]', '[data-e2e="nav-profile"]'],
+    cookieNames: [...TIKTOK_SESSION_COOKIES, 'uid_tt'],
+};
+
 // ============== User Agent ==============
 export const USER_AGENT = 'Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) StreamlabsDesktop/1.17.0 Chrome/122.0.6261.156 Electron/29.3.1 Safari/537.36';
 
+// ============== HTTP Client ==============
+export const HTTP_CONFIG = {
+    TIMEOUT_MS: 15000,
+    CONNECTIVITY_TIMEOUT_MS: 5000,
+    PROFILE_CACHE_TTL_MS: 60 * 1000,
+} as const;
+
 // ============== API Query Parameters ==============
 export const QUERY_PARAMS = {
     DEFAULT_CATEGORY: 'gaming',
     MAX_CATEGORY_LENGTH: 25,
     DEFAULT_AUDIENCE_TYPE: '0',
     DEFAULT_LIMIT_CATEGORIES: 20,
+    DEFAULT_HISTORY_LIMIT: 10,
+    // Counted in Unicode code points, matching TikTok's character-based limit
+    MAX_TITLE_LENGTH: 100,
 } as const;
 
+/**
+ * Audience types offered when /info does not list them
+ */
+export const DEFAULT_AUDIENCE_TYPES: ReadonlyArray<{ id: string; label: string }> = [
+    { id: '0', label: 'Everyone' },
+    { id: '1', label: 'Mature (18+)' },
+];
+
 // ============== WebPreferences ==============
 export const WEB_PREFERENCES = {
     NODE_INTEGRATION: false,
@@ -94,9 +224,30 @@ export const ERROR_MESSAGES = {
     AUTH_FAILED: 'Unknown error during login',
     NO_STREAM_API: 'Stream API not initialized',
     NO_STREAM_ID: 'No stream ID provided to end the stream',
+    MISSING_STREAM_INFO: 'Stream started but the response had no RTMP URL/key',
+    MISSING_TOKEN: 'Missing Streamlabs token, please log in first',
+    STREAM_UNAUTHORIZED: 'Streamlabs rejected the token, please log in again',
+    NO_ACTIVE_STREAM: 'There is no active stream to end',
+    STREAM_END_FAILED: 'The current stream could not be ended; it is still live',
+    STREAM_ALREADY_LIVE: 'A stream is already live with a different title or category; end or restart it first',
+    TITLE_TOO_LONG: (max: number) => `Stream title must be at most ${max} characters`,
+    INVALID_HISTORY_LIMIT: 'Stream history limit must be a positive whole number',
     JSON_PARSE_ERROR: 'JSON Parse Error',
     NO_CODE_VERIFIER: 'No CodeVerifier found',
+    INVALID_AUTH_CODE: 'Authorization code must be non-empty and contain only URL-safe characters',
+    INVALID_TOKEN_RESPONSE: (detail: string) => `No OAuth token found in auth response: ${detail}`,
+    TOKEN_EXCHANGE_FAILED: (attempts: number) => `Streamlabs did not provide a token after ${attempts} attempts`,
+    NO_LOGIN_WINDOW: 'No login window is open',
+    SCRIPT_TOO_LARGE: (max: number) => `Script exceeds the ${max} byte limit`,
+    SCRIPT_UNSAFE: 'Script must not navigate the login window',
+    PAYLOAD_TOO_LARGE: (max: number) => `Captured payload exceeds the ${max} byte limit`,
+    INVALID_SESSION_BUNDLE: 'Not a session bundle exported by this app version',
+    REDACTED_SESSION_BUNDLE: 'Redacted session bundles cannot be imported',
+    INVALID_COOKIE_FILE: 'Cookie file must contain a JSON array of cookies',
+    NO_TIKTOK_SESSION: 'No TikTok session cookies found (expected sessionid)',
     WINDOW_CLOSED: 'Window closed by user',
+    LOGIN_IN_PROGRESS: 'A login window is already open',
+    LOGIN_CANCELLED: 'Login cancelled',
     FETCH_FAILED: 'Fetch failed',
 } as const;
 
@@ -110,13 +261,20 @@ export const CONSOLE_MESSAGES = {
         `[StreamAPI] Truncating search query from "${original}" to "${truncated}" due to API limits.`,
     API_SEARCH_RESULTS: (query: string, count: number) => 
         `[StreamAPI] Found ${count} matches for "${query}"`,
+    API_AUDIENCE_TYPES_FALLBACK: '[StreamAPI] Using default audience types:',
     API_START_ERROR: 'Error starting stream, unexpected response:',
+    API_STREAM_STARTED: (id: string, url: string, maskedKey: string) =>
+        `[StreamAPI] Stream ${id} started at ${url} (key ${maskedKey})`,
     API_END_ERROR: 'Error ending stream:',
     API_INFO_ERROR: 'Error getting info:',
     AUTH_SAVED_TOKEN: '[AuthManager] Using saved token from tokens.json',
+    AUTH_CONFIG_TOKEN: '[AuthManager] Using token from STREAM_TOKEN / config.json',
     AUTH_LOAD_FAIL: '[AuthManager] Failed to load saved tokens:',
     AUTH_START_FLOW: '[AuthManager] Starting authentication via internal Electron window...',
     AUTH_SAVED: '[AuthManager] Tokens saved to tokens.json',
+    TOKEN_REFRESH_SCHEDULED: (at: string) => `[TokenRefresher] Refresh scheduled for ${at}`,
+    TOKEN_REFRESHED: '[TokenRefresher] Token refreshed',
+    TOKEN_REFRESH_FAILED: '[TokenRefresher] Token refresh failed:',
     ELECTRON_PRELOAD: (path: string) => `[Electron-Login] Preload path: ${path}`,
     ELECTRON_NAVIGATE: '[Electron-Login] Navigating to TikTok login...',
     ELECTRON_LOGIN_DETECTED: '[Electron-Login] Login detected. Preparing to navigate to Streamlabs Auth...',
@@ -130,6 +288,9 @@ export const CONSOLE_MESSAGES = {
     ELECTRON_AUTH_SUCCESS: '[Electron-Login] Auth data received successfully',
     ELECTRON_ERROR_RESULT: (result: string) => `[Electron-Login] Error in fetch result: ${result}`,
     ELECTRON_COOKIES_LOADED: '[Electron-Login] Cookies loaded.',
+    ELECTRON_COOKIE_SET_FAIL: (name: string) => `[Electron-Login] Skipping cookie that could not be restored: ${name}`,
+    CUSTOM_SCRIPT_INJECTED: (label: string) => `[Electron-Login] Injected custom script: ${label}`,
+    COOKIES_MALFORMED: (file: string) => `[Cookies] Ignoring malformed ${file}, login will be required again:`,
     ELECTRON_COOKIES_SAVE_ERROR: '[Electron-Login] Failed to save cookies:',
     ELECTRON_JS_ERROR: (message: string) => `[Electron-Login] executeJavaScript error: ${message}`,
 } as const;
@@ -14,72 +14,295 @@ if (!process.versions.electron) {
     process.exit(0);
 }
 
-import { AuthManager } from './auth/AuthManager';
+import { AuthManager, checkLoginState, validateToken, getAppStatus, resolveStartupToken } from './auth/AuthManager';
+import { captureStats } from './auth/captureStats';
+import { TokenRefresher } from './auth/tokenRefresher';
+import { AuthError } from './auth/errors';
+import { listSavedAccounts } from './auth/accounts';
+import { exportSessionBundle, importSessionBundle } from './auth/sessionBundle';
+import { clearBrowserCookies } from './auth/electron-login';
+import { importCookies, clearTikTokCookies, verifyTikTokCookies, loadStoredCookies, validateCookies, getCapturedUserInfo, type CookieFileFormat } from './auth/cookies';
 import { StreamAPI } from './api/StreamAPI';
-import { IPC_CHANNELS, CONSOLE_MESSAGES, PATHS } from './constants';
+import { IPC_CHANNELS, CONSOLE_MESSAGES, ERROR_MESSAGES, PATHS } from './constants';
 import { createIpcHandler } from './utils/ipcHandler';
 import { MainWindowManager } from './utils/windowManager';
-import { TokenStorage } from './utils/fileUtils';
+import { TokenStorage, listCredentialFiles, deleteCredentialFile, setPersistSecrets, setCredentialDir, getCredentialDir } from './utils/fileUtils';
+import { ConfigManager, toRendererConfig } from './config/ConfigManager';
+import { runConnectivityCheck } from './utils/connectivity';
+import { getVersionInfo } from './utils/versionInfo';
+import { StreamManager, formatIngestForObs } from './stream/StreamManager';
 
 // Main Application Logic
 async function init() {
     let streamAPI: StreamAPI | null = null;
     let token: string | null = null;
+    let authManager: AuthManager | null = null;
 
     const mainWindow = new MainWindowManager();
+    const configManager = new ConfigManager(PATHS.CONFIG);
+    const streamManager = new StreamManager(() => streamAPI, configManager, () => token);
+
+    const createStreamApi = (apiToken: string) =>
+        new StreamAPI(apiToken, undefined, configManager.get('userAgent'));
+
+    const createAuthManager = () => new AuthManager({
+        emit: (channel, payload) => mainWindow.getWindow()?.webContents.send(channel, payload),
+    });
+
+    const applyToken = (newToken: string) => {
+        token = newToken;
+        if (streamAPI) {
+            streamAPI.setToken(newToken);
+        } else {
+            streamAPI = createStreamApi(newToken);
+        }
+        startTokenRefresher();
+    };
+
+    const tokenRefresher = new TokenRefresher({
+        onRefreshed: refreshedToken => {
+            applyToken(refreshedToken);
+            mainWindow.getWindow()?.webContents.send(IPC_CHANNELS.AUTH_TOKEN_REFRESHED, { success: true });
+        },
+        onError: error => {
+            mainWindow.getWindow()?.webContents.send(IPC_CHANNELS.AUTH_TOKEN_REFRESH_FAILED, {
+                success: false,
+                error: error.message,
+                kind: error instanceof AuthError ? error.kind : undefined,
+            });
+        },
+    });
+
+    // The refresh endpoint is unverified, so background renewal is opt-in
+    const startTokenRefresher = () => {
+        if (configManager.getBool('tokenRefresh')) tokenRefresher.start();
+    };
 
     // Check for saved token on startup and initialize StreamAPI if available
     function initializeWithSavedToken() {
-        const tokenStorage = new TokenStorage(PATHS.TOKENS);
-        const savedToken = tokenStorage.get();
-        if (savedToken) {
+        const startup = resolveStartupToken(configManager.get('token'));
+        if (!startup) return;
+
+        token = startup.token;
+        streamAPI = createStreamApi(token);
+        if (startup.source === 'config') {
+            // An overridden token has no refresh token to renew it with
+            console.log(CONSOLE_MESSAGES.AUTH_CONFIG_TOKEN);
+        } else {
             console.log(CONSOLE_MESSAGES.AUTH_SAVED_TOKEN);
-            token = savedToken;
-            streamAPI = new StreamAPI(token);
+            startTokenRefresher();
         }
     }
 
     function setupIPC() {
         createIpcHandler(IPC_CHANNELS.AUTH_LOGIN, async () => {
             console.log(CONSOLE_MESSAGES.AUTH_START);
-            const authManager = new AuthManager();
-            token = await authManager.retrieveToken();
-            streamAPI = new StreamAPI(token);
+            authManager ??= createAuthManager();
+            applyToken(await authManager.retrieveToken());
+            console.log(CONSOLE_MESSAGES.AUTH_SUCCESS);
+            return { success: true };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_METRICS, async () => {
+            return authManager?.getAuthMetrics() ?? null;
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_CANCEL_LOGIN, async () => {
+            return { success: authManager?.cancelLogin() ?? false };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_PREPARE_LOGIN, async () => {
+            authManager ??= createAuthManager();
+            return { authUrl: await authManager.prepareLogin() };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_SUBMIT_CODE, async (_: any, code: string) => {
+            // Reuse the prepared/running login's manager so its verifier matches the code
+            authManager ??= createAuthManager();
+            applyToken(await authManager.submitAuthCode(code));
             console.log(CONSOLE_MESSAGES.AUTH_SUCCESS);
             return { success: true };
         });
 
+        // Runs arbitrary JS in the window holding the TikTok session, so it is a
+        // development tool only: never registered in packaged builds unless opted in
+        if (!app.isPackaged || configManager.getBool('debugScriptInjection')) {
+            createIpcHandler(IPC_CHANNELS.AUTH_INJECT_SCRIPT, async (_: any, label: string, script: string) => {
+                if (!authManager) {
+                    throw new Error(ERROR_MESSAGES.NO_LOGIN_WINDOW);
+                }
+                await authManager.injectCustomScript(label, script);
+                return { success: true };
+            });
+        }
+
+        createIpcHandler(IPC_CHANNELS.APP_STATUS, async () => {
+            return getAppStatus({ token, streamLive: Boolean(streamAPI?.getCurrentStreamId()) });
+        });
+
+        createIpcHandler(IPC_CHANNELS.APP_VERSION_INFO, async () => {
+            return getVersionInfo();
+        });
+
+        createIpcHandler(IPC_CHANNELS.APP_CONNECTIVITY, async () => {
+            return runConnectivityCheck();
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_STATE, async (_: any, validate?: boolean) => {
+            return checkLoginState({ validate: Boolean(validate) });
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_VALIDATE_TOKEN, async () => {
+            return validateToken(token ?? new TokenStorage(PATHS.TOKENS).get());
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_IMPORT_COOKIES, async (_: any, filePath: string, format: CookieFileFormat) => {
+            const cookies = importCookies(filePath, format);
+            return { success: true, count: cookies.length };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_CLEAR_TIKTOK_COOKIES, async () => {
+            const removedFile = clearTikTokCookies();
+            const removedBrowserCookies = await clearBrowserCookies();
+            return { success: true, removedFile, removedBrowserCookies };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_VALIDATE_COOKIES, async () => {
+            return validateCookies(loadStoredCookies());
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_LOGIN_INDICATORS, async () => {
+            return authManager?.getLoginIndicators() ?? [];
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_CAPTURE_STATS, async () => {
+            return captureStats.series();
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_EXPORT_SESSION, async (_: any, redact: boolean = true) => {
+            return exportSessionBundle({ redact });
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_IMPORT_SESSION, async (_: any, bundle: unknown) => {
+            const result = importSessionBundle(bundle);
+            if (result.tokenImported) {
+                initializeWithSavedToken();
+            }
+            return { success: true, ...result };
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_VERIFY_COOKIES, async () => {
+            return verifyTikTokCookies();
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_LIST_ACCOUNTS, async () => {
+            return listSavedAccounts();
+        });
+
+        createIpcHandler(IPC_CHANNELS.AUTH_CAPTURED_USER, async () => {
+            return getCapturedUserInfo(loadStoredCookies(), authManager?.getCapturedUser());
+        });
+
         createIpcHandler(IPC_CHANNELS.STREAM_INFO, async () => {
             return streamAPI?.getInfo() ?? null;
         }, { requireStreamApi: true, getStreamApi: () => streamAPI });
 
         createIpcHandler(IPC_CHANNELS.STREAM_SEARCH, async (_: any, query: string) => {
-            return streamAPI?.search(query) ?? [];
-        }, { requireStreamApi: true, getStreamApi: () => streamAPI });
+            return streamManager.search(query);
+        });
 
         createIpcHandler(IPC_CHANNELS.STREAM_START, async (_: any, { title, category }: any) => {
-            return streamAPI?.start(title, category) ?? null;
-        }, { requireStreamApi: true, getStreamApi: () => streamAPI });
+            return streamManager.start(title, category);
+        });
 
         createIpcHandler(IPC_CHANNELS.STREAM_END, async () => {
-            return streamAPI?.end() ?? false;
-        }, { requireStreamApi: true, getStreamApi: () => streamAPI });
+            return streamManager.end();
+        });
+
+        createIpcHandler(IPC_CHANNELS.STREAM_RESTART, async (_: any, { title, category }: any) => {
+            return streamManager.restart(title, category);
+        });
 
         createIpcHandler(IPC_CHANNELS.USER_PROFILE, async () => {
             return streamAPI?.getUserProfile() ?? null;
         }, { requireStreamApi: true, getStreamApi: () => streamAPI });
 
         createIpcHandler(IPC_CHANNELS.STREAM_CURRENT, async () => {
-            return streamAPI?.getCurrentStream() ?? null;
-        }, { requireStreamApi: true, getStreamApi: () => streamAPI });
+            return streamManager.getCurrentStream();
+        });
+
+        createIpcHandler(IPC_CHANNELS.STREAM_INGEST, async (_: any, format?: 'obs') => {
+            const ingest = streamManager.getIngest();
+            return format === 'obs' ? formatIngestForObs(ingest) : ingest;
+        });
+
+        createIpcHandler(IPC_CHANNELS.STREAM_AUDIENCE_TYPES, async () => {
+            return streamManager.getAudienceTypes();
+        });
+
+        createIpcHandler(IPC_CHANNELS.STREAM_HISTORY, async (_: any, limit?: number) => {
+            return streamManager.getStreamHistory(limit);
+        });
+
+        createIpcHandler(IPC_CHANNELS.CONFIG_LAST_STREAM, async () => {
+            return streamManager.getLastStreamInfo();
+        });
+
+        createIpcHandler(IPC_CHANNELS.CONFIG_GET_REMEMBER_STREAM_INFO, async () => {
+            return streamManager.shouldRememberStreamInfo();
+        });
+
+        createIpcHandler(IPC_CHANNELS.CONFIG_SET_REMEMBER_STREAM_INFO, async (_: any, enabled: boolean) => {
+            streamManager.setRememberStreamInfo(Boolean(enabled));
+            return { success: true };
+        });
+
+        createIpcHandler(IPC_CHANNELS.FILES_LIST, async () => {
+            return listCredentialFiles();
+        });
+
+        createIpcHandler(IPC_CHANNELS.FILES_SET_DIR, async (_: any, dir: string | null) => {
+            setCredentialDir(dir);
+            // A login in progress would save its cookies/token to the directory it started in
+            authManager?.cancelLogin();
+            tokenRefresher.stop();
+            token = null;
+            streamAPI = null;
+            initializeWithSavedToken();
+            return { success: true, dir: getCredentialDir() };
+        });
+
+        createIpcHandler(IPC_CHANNELS.FILES_DELETE, async (_: any, name: string) => {
+            const deleted = deleteCredentialFile(name);
+            // Deleting tokens.json logs out; stop renewing a token that is gone
+            if (deleted && name === PATHS.TOKENS) {
+                tokenRefresher.stop();
+            }
+            return { success: deleted };
+        });
     }
 
     await app.whenReady();
+    configManager.load();
+    setPersistSecrets(configManager.getBool('persistSecrets') ?? true);
     initializeWithSavedToken();
     setupIPC();
     mainWindow.create();
     mainWindow.load();
 
+    const unsubscribeCaptures = captureStats.subscribe(sample => {
+        mainWindow.getWindow()?.webContents.send(IPC_CHANNELS.AUTH_CAPTURE_UPDATED, sample);
+    });
+    app.on('will-quit', unsubscribeCaptures);
+    app.on('will-quit', () => tokenRefresher.stop());
+
+    if (configManager.getBool('watchConfig')) {
+        const stopWatching = configManager.watch(config => {
+            mainWindow.getWindow()?.webContents.send(IPC_CHANNELS.CONFIG_CHANGED, toRendererConfig(config));
+        });
+        app.on('will-quit', stopWatching);
+    }
+
     app.on('window-all-closed', () => {
         if (process.platform !== 'darwin') app.quit();
     });
@@ -0,0 +1,262 @@
+import type { AudienceType, CurrentStream, StreamAPI, StreamCategory, StreamInfo, StreamSummary } from '../api/StreamAPI';
+import type { ConfigManager } from '../config/ConfigManager';
+import { ERROR_MESSAGES, PATHS, QUERY_PARAMS } from '../constants';
+import { hasStreamlabsToken } from '../utils/fileUtils';
+import { ApiError } from '../utils/apiClient';
+import { codePointLength, sanitizeText } from '../utils/strUtils';
+
+export type StreamErrorKind = 'missing_token' | 'unauthorized' | 'no_active_stream' | 'invalid_parameters' | 'already_live' | 'end_failed';
+
+/**
+ * Stream failures the UI can react to (re-login, reset the live state, ...)
+ */
+export class StreamError extends Error {
+    constructor(public readonly kind: StreamErrorKind, message: string) {
+        super(message);
+        this.name = 'StreamError';
+    }
+}
+
+export interface LastStreamInfo {
+    title?: string;
+    category?: string;
+}
+
+export interface StreamIngest {
+    server: string;
+    key: string;
+}
+
+/**
+ * OBS "Custom" service settings, ready to paste
+ */
+export function formatIngestForObs(ingest: StreamIngest): string {
+    return `Server: ${ingest.server}\nStream Key: ${ingest.key}`;
+}
+
+/**
+ * Validate stream parameters before they reach the API.
+ * Length is counted in Unicode code points, so emoji/CJK count as one character.
+ */
+export function validateStreamParams(title: string): void {
+    if (codePointLength(title) > QUERY_PARAMS.MAX_TITLE_LENGTH) {
+        throw new StreamError('invalid_parameters', ERROR_MESSAGES.TITLE_TOO_LONG(QUERY_PARAMS.MAX_TITLE_LENGTH));
+    }
+}
+
+/**
+ * Reject history limits that would become `limit=NaN` in the query or make the
+ * result slicing drop items (negative, fractional)
+ */
+export function validateHistoryLimit(limit: unknown): void {
+    if (!Number.isSafeInteger(limit) || (limit as number) < 1) {
+        throw new StreamError('invalid_parameters', ERROR_MESSAGES.INVALID_HISTORY_LIMIT);
+    }
+}
+
+/**
+ * Coordinates stream actions with the persisted app configuration
+ */
+export class StreamManager {
+    private active: StreamInfo | null = null;
+    /** Title/category of the active or starting stream; null when adopted after a restart */
+    private activeParams: LastStreamInfo | null = null;
+    private starting: Promise<StreamInfo> | null = null;
+    private startingParams: LastStreamInfo | null = null;
+
+    constructor(
+        private getStreamApi: () => StreamAPI | null,
+        private config: ConfigManager,
+        private getToken: () => string | null = () => null
+    ) {}
+
+    async search(query: string): Promise<StreamCategory[]> {
+        return this.call(api => api.search(query));
+    }
+
+    /**
+     * Start a stream and remember the title/category for the next session
+     * (unless rememberStreamInfo is off).
+     * While a start is in flight, or the stream started earlier is still live
+     * according to the API, that stream is returned instead of creating another
+     * one (e.g. on a double-click). Asking for a different title/category then
+     * fails with 'already_live'.
+     */
+    async start(title: string, category: string): Promise<StreamInfo> {
+        title = sanitizeText(title);
+        validateStreamParams(title);
+        if (this.starting) {
+            this.assertSameStream(this.startingParams, title, category);
+            return this.starting;
+        }
+        if (this.active) {
+            const active = this.active;
+            const live = await this.isStillLive(active);
+            if (live && this.active === active) {
+                this.assertSameStream(this.activeParams, title, category);
+                return active;
+            }
+        }
+        // Another call may have started a stream while the API was checked
+        if (this.starting) {
+            this.assertSameStream(this.startingParams, title, category);
+            return this.starting;
+        }
+
+        this.clearActive();
+        this.startingParams = { title, category };
+        this.starting = this.startStream(title, category).finally(() => {
+            this.starting = null;
+            this.startingParams = null;
+        });
+        return this.starting;
+    }
+
+    /**
+     * Whether `stream` is still the live stream. Only a definite "nothing is live"
+     * answer counts as ended; lookup failures propagate so a short outage cannot
+     * lead to a second broadcast.
+     */
+    private async isStillLive(stream: StreamInfo): Promise<boolean> {
+        const current = await this.call(api => api.getCurrentStream());
+        if (!current) return false;
+        if (current.id !== stream.id) {
+            // Some other stream is live (e.g. started elsewhere); don't add another
+            throw new StreamError('already_live', ERROR_MESSAGES.STREAM_ALREADY_LIVE);
+        }
+        return true;
+    }
+
+    private assertSameStream(params: LastStreamInfo | null, title: string, category: string): void {
+        if (params && (params.title !== title || params.category !== category)) {
+            throw new StreamError('already_live', ERROR_MESSAGES.STREAM_ALREADY_LIVE);
+        }
+    }
+
+    private clearActive(): void {
+        this.active = null;
+        this.activeParams = null;
+    }
+
+    private async startStream(title: string, category: string): Promise<StreamInfo> {
+        const info = await this.call(api => api.start(title, category));
+        this.active = info;
+        this.activeParams = { title, category };
+        if (this.shouldRememberStreamInfo()) {
+            this.config.save({ title, game: category });
+        }
+        return info;
+    }
+
+    async end(): Promise<boolean> {
+        const ended = await this.call(api => {
+            if (!api.getCurrentStreamId()) {
+                throw new StreamError('no_active_stream', ERROR_MESSAGES.NO_ACTIVE_STREAM);
+            }
+            return api.end();
+        });
+        if (ended) {
+            this.clearActive();
+        }
+        return ended;
+    }
+
+    /**
+     * Recover the live stream after an app restart
+     */
+    async getCurrentStream(): Promise<CurrentStream | null> {
+        const current = await this.call(api => api.getCurrentStream());
+        if (current?.rtmpUrl && current.streamKey) {
+            this.active = { id: current.id, rtmpUrl: current.rtmpUrl, streamKey: current.streamKey };
+            this.activeParams = null;
+        }
+        return current;
+    }
+
+    async getAudienceTypes(): Promise<AudienceType[]> {
+        return this.call(api => api.getAudienceTypes());
+    }
+
+    async getStreamHistory(limit?: number | null): Promise<StreamSummary[]> {
+        // null/undefined (e.g. omitted over IPC) uses the API default
+        if (limit == null) return this.call(api => api.getStreamHistory());
+        validateHistoryLimit(limit);
+        return this.call(api => api.getStreamHistory(limit));
+    }
+
+    /**
+     * RTMP target of the live stream, only returned on explicit request and never logged
+     */
+    getIngest(): StreamIngest {
+        if (!this.active || !this.getStreamApi()?.getCurrentStreamId()) {
+            throw new StreamError('no_active_stream', ERROR_MESSAGES.NO_ACTIVE_STREAM);
+        }
+        return { server: this.active.rtmpUrl, key: this.active.streamKey };
+    }
+
+    /**
+     * End the current stream (if any) and start a new one with the same settings.
+     * "Not live" failures while ending are ignored since the stream already dropped.
+     */
+    async restart(title: string, category: string): Promise<StreamInfo> {
+        try {
+            const ended = await this.end();
+            // The API declined to end the stream, so it is still live
+            if (!ended) {
+                throw new StreamError('end_failed', ERROR_MESSAGES.STREAM_END_FAILED);
+            }
+        } catch (error) {
+            // Only "there is no stream to end" lets the restart go on; any other
+            // failure may leave the old stream live, so starting another would orphan it
+            const notLive = (error instanceof StreamError && error.kind === 'no_active_stream') ||
+                (error instanceof ApiError && error.status === 404);
+            if (!notLive) throw error;
+        }
+        this.clearActive();
+        return this.start(title, category);
+    }
+
+    /**
+     * Title/category used by the previous stream, for prefilling the UI
+     */
+    getLastStreamInfo(): LastStreamInfo {
+        return {
+            title: this.config.get('title'),
+            category: this.config.get('game'),
+        };
+    }
+
+    shouldRememberStreamInfo(): boolean {
+        return this.config.getBool('rememberStreamInfo') ?? true;
+    }
+
+    setRememberStreamInfo(enabled: boolean): void {
+        this.config.save({ rememberStreamInfo: enabled });
+    }
+
+    /**
+     * Run an API call, mapping auth failures into StreamError
+     */
+    private async call<T>(action: (api: StreamAPI) => Promise<T>): Promise<T> {
+        const streamApi = this.requireStreamApi();
+        try {
+            return await action(streamApi);
+        } catch (error) {
+            if (error instanceof ApiError && error.kind === 'unauthorized') {
+                throw new StreamError('unauthorized', ERROR_MESSAGES.STREAM_UNAUTHORIZED);
+            }
+            throw error;
+        }
+    }
+
+    private requireStreamApi(): StreamAPI {
+        if (!hasStreamlabsToken(this.getToken(), PATHS.TOKENS)) {
+            throw new StreamError('missing_token', ERROR_MESSAGES.MISSING_TOKEN);
+        }
+        const streamApi = this.getStreamApi();
+        if (!streamApi) {
+            throw new Error(ERROR_MESSAGES.NO_STREAM_API);
+        }
+        return streamApi;
+    }
+}
@@ -1,7 +1,7 @@
 // App State
 const state = { authorized: false, category: '', stream: null };
 const $ = id => document.getElementById(id);
-const api = channel => window.electronAPI.invoke(channel);
+const api = (channel, ...args) => window.electronAPI.invoke(channel, ...args);
 
 // Logger
 const log = (() => {
@@ -71,6 +71,10 @@ async function loadCategories() {
     ).join('');
     
     container.querySelectorAll('.category-pill').forEach((el, i) => {
+        if (cats[i].game_mask_id === state.category) {
+            $('game-search').value = cats[i].full_name;
+            el.classList.add('active');
+        }
         el.onclick = () => {
             $('game-search').value = cats[i].full_name;
             state.category = cats[i].game_mask_id;
@@ -102,19 +106,20 @@ async function startStream() {
         show($('start-btn'), false);
         show($('stop-btn'), true);
     } else {
-        log('Failed to start', 'error');
+        log(res?.error ? `Failed to start: ${res.error}` : 'Failed to start', 'error');
     }
 }
 
 async function stopStream() {
     log('Stopping...', 'info');
-    if (await api('stream:end')) {
+    const res = await api('stream:end');
+    if (res === true) {
         log('Stopped', 'success');
         show($('result-section'), false);
         show($('start-btn'), true);
         show($('stop-btn'), false);
     } else {
-        log('Failed to stop', 'error');
+        log(res?.error ? `Failed to stop: ${res.error}` : 'Failed to stop', 'error');
     }
 }
 
@@ -142,6 +147,21 @@ function init() {
         };
     });
 
+    window.electronAPI.on('auth:token-exchange-progress', ({ status }) => {
+        log(`Token exchange: ${status.replace(/_/g, ' ')}`, 'info');
+    });
+    window.electronAPI.on('auth:token-exchange-failed', ({ error }) => {
+        log(`Token exchange failed: ${error}`, 'error');
+    });
+    window.electronAPI.on('auth:token-refresh-failed', ({ error }) => {
+        log(`Token refresh failed: ${error}`, 'error');
+    });
+
+    window.electronAPI.on('config:changed', config => {
+        if (config?.title) $('stream-title').value = config.title;
+        log('Config reloaded', 'info');
+    });
+
     document.onclick = e => {
         if (!e.target.closest('.search-container')) show($('search-results'), false);
     };
@@ -162,9 +182,24 @@ function updateUI() {
     show($('user-info'), state.authorized);
     
     if (state.authorized) {
-        loadCategories();
+        api('config:last-stream').then(last => {
+            if (last?.title) $('stream-title').value = last.title;
+            if (last?.category) state.category = last.category;
+            loadCategories();
+        });
+        api('stream:current').then(current => {
+            if (!current?.id) return;
+            log('Recovered live stream', 'info');
+            if (current.rtmpUrl) {
+                $('rtmp-url').value = current.rtmpUrl;
+                $('stream-key').value = current.streamKey || '';
+                show($('result-section'), true);
+            }
+            show($('start-btn'), false);
+            show($('stop-btn'), true);
+        });
         api('user:profile').then(p => {
-            if (p) {
+            if (p && p.success !== false) {
                 $('user-avatar').src = p.avatar_thumb || '';
                 $('user-name').textContent = p.display_name || p.username || '';
             }
@@ -1,5 +1,35 @@
-import axios, { type AxiosInstance, type AxiosError } from 'axios';
-import { USER_AGENT } from '../constants';
+import type { AxiosInstance, AxiosError } from 'axios';
+import { createHttpClient } from './http';
+import { safeSlice } from './strUtils';
+
+export type ApiErrorKind = 'unauthorized' | 'http' | 'network' | 'missing_data';
+
+/**
+ * Error raised by strict requests so callers can react to the failure kind
+ */
+export class ApiError extends Error {
+    constructor(
+        public readonly kind: ApiErrorKind,
+        message: string,
+        public readonly status?: number
+    ) {
+        super(message);
+        this.name = 'ApiError';
+    }
+
+    static from(error: unknown): ApiError {
+        if (error instanceof ApiError) return error;
+        const axiosError = error as AxiosError;
+        const status = axiosError?.response?.status;
+        if (status === 401) {
+            return new ApiError('unauthorized', axiosError.message || 'Unauthorized', status);
+        }
+        if (status) {
+            return new ApiError('http', axiosError.message || `HTTP ${status}`, status);
+        }
+        return new ApiError('network', axiosError?.message || 'Network Error');
+    }
+}
 
 /**
  * Base API client with common patterns
@@ -10,13 +40,21 @@ export abstract class BaseApiClient {
 
     constructor(baseURL: string, token?: string, customUserAgent?: string) {
         this.baseURL = baseURL;
-        this.client = axios.create({
-            baseURL,
-            headers: {
-                'User-Agent': customUserAgent || USER_AGENT,
-                ...(token && { 'Authorization': `Bearer ${token}` }),
-            },
-        });
+        this.client = createHttpClient({ baseURL, token, userAgent: customUserAgent });
+    }
+
+    /**
+     * Replace the bearer token without rebuilding the client or losing its state
+     */
+    setToken(token: string): void {
+        this.client.defaults.headers['Authorization'] = `Bearer ${token}`;
+    }
+
+    /**
+     * Stop sending a bearer token
+     */
+    clearToken(): void {
+        delete this.client.defaults.headers['Authorization'];
     }
 
     /**
@@ -32,6 +70,19 @@ export abstract class BaseApiClient {
         }
     }
 
+    /**
+     * GET request that throws an ApiError instead of returning null
+     */
+    protected async getStrict<T = any>(endpoint: string): Promise<T> {
+        try {
+            const response = await this.client.get(endpoint);
+            return response.data;
+        } catch (error) {
+            this.handleError('GET', endpoint, error);
+            throw ApiError.from(error);
+        }
+    }
+
     /**
      * Safe POST request with error handling
      */
@@ -45,6 +96,19 @@ export abstract class BaseApiClient {
         }
     }
 
+    /**
+     * POST request that throws an ApiError instead of returning null
+     */
+    protected async postStrict<T = any>(endpoint: string, data?: any): Promise<T> {
+        try {
+            const response = await this.client.post(endpoint, data);
+            return response.data;
+        } catch (error) {
+            this.handleError('POST', endpoint, error);
+            throw ApiError.from(error);
+        }
+    }
+
     /**
      * Safe request with custom config
      */
@@ -100,8 +164,15 @@ export function toFormData(data: Record<string, string>): FormData {
 }
 
 /**
- * Truncate string to max length
+ * Truncate string to max length, counted in code points
  */
 export function truncate(text: string, maxLength: number): string {
-    return text.length > maxLength ? text.substring(0, maxLength) : text;
+    return safeSlice(text, 0, Math.max(0, maxLength));
+}
+
+/**
+ * Wait for the given number of milliseconds
+ */
+export function sleep(ms: number): Promise<void> {
+    return new Promise(resolve => setTimeout(resolve, ms));
 }
@@ -0,0 +1,45 @@
+import { API_BASE_URL, API_ENDPOINTS, HTTP_CONFIG } from '../constants';
+import { buildHeaders } from './http';
+
+export interface EndpointCheck {
+    reachable: boolean;
+    latencyMs?: number;
+    reason?: string;
+}
+
+export interface ConnectivityReport {
+    tiktok: EndpointCheck;
+    streamlabs: EndpointCheck;
+}
+
+/**
+ * Any HTTP response counts as reachable; DNS, connection and timeout failures do not
+ */
+export async function checkEndpoint(url: string, timeoutMs: number = HTTP_CONFIG.CONNECTIVITY_TIMEOUT_MS): Promise<EndpointCheck> {
+    const startedAt = Date.now();
+    try {
+        await fetch(url, {
+            method: 'HEAD',
+            redirect: 'manual',
+            headers: buildHeaders(),
+            signal: AbortSignal.timeout(timeoutMs),
+        });
+        return { reachable: true, latencyMs: Date.now() - startedAt };
+    } catch (error) {
+        return { reachable: false, reason: error instanceof Error ? error.message : String(error) };
+    }
+}
+
+/**
+ * Check the TikTok login page and the Streamlabs API in parallel
+ */
+export async function runConnectivityCheck(
+    endpoints: { tiktok: string; streamlabs: string } = { tiktok: API_ENDPOINTS.TIKTOK_LOGIN, streamlabs: API_BASE_URL },
+    timeoutMs?: number
+): Promise<ConnectivityReport> {
+    const [tiktok, streamlabs] = await Promise.all([
+        checkEndpoint(endpoints.tiktok, timeoutMs),
+        checkEndpoint(endpoints.streamlabs, timeoutMs),
+    ]);
+    return { tiktok, streamlabs };
+}
@@ -1,6 +1,8 @@
+import crypto from 'node:crypto';
 import fs from 'fs';
 import path from 'path';
 import { app } from 'electron';
+import { tryParseTokenResponse } from '../auth/tokenResponse';
 
 export type JsonData = Record<string, unknown>;
 export type JsonArray = unknown[];
@@ -32,6 +34,104 @@ export function resolveAppPath(...pathSegments: string[]): string {
     return path.resolve(getAppBasePath(), ...pathSegments);
 }
 
+/**
+ * Files holding tokens/cookies, which can be kept in memory only (see setPersistSecrets)
+ * or redirected to another directory (see setCredentialDir)
+ */
+const SECRET_FILES = ['cookies.json', 'tokens.json', 'user_info.json'];
+let credentialDir: string | null = null;
+
+/**
+ * Store cookies.json/tokens.json in `dir` (e.g. a per-profile folder) instead of
+ * the app directory. Pass null to go back to the default location.
+ * `dir` must be an absolute path to an existing, writable directory.
+ */
+export function setCredentialDir(dir: string | null): void {
+    if (dir !== null) {
+        assertCredentialDir(dir);
+    }
+    const previous = getCredentialDir();
+    credentialDir = dir === null ? null : path.resolve(dir);
+    console.log(`[FileUtils] Credential directory changed: ${previous} -> ${getCredentialDir()}`);
+}
+
+function assertCredentialDir(dir: string): void {
+    if (typeof dir !== 'string' || !path.isAbsolute(dir)) {
+        throw new CredentialError('invalid_dir', `[FileUtils] Credential directory must be an absolute path: ${dir}`);
+    }
+    try {
+        if (!fs.statSync(dir).isDirectory()) {
+            throw new CredentialError('invalid_dir', `[FileUtils] Not a directory: ${dir}`);
+        }
+        fs.accessSync(dir, fs.constants.W_OK);
+    } catch (error) {
+        if (error instanceof CredentialError) throw error;
+        throw new CredentialError('invalid_dir', `[FileUtils] Credential directory is missing or not writable: ${dir}`);
+    }
+}
+
+export function getCredentialDir(): string {
+    return credentialDir ?? getAppBasePath();
+}
+
+/**
+ * Resolve a data file, honoring the credential directory for secret files
+ */
+export function resolveDataPath(filename: string): string {
+    if (credentialDir && !path.isAbsolute(filename) && SECRET_FILES.includes(path.basename(filename))) {
+        return path.resolve(credentialDir, filename);
+    }
+    return resolveAppPath(filename);
+}
+const memorySecrets = new Map<string, string>();
+let persistSecrets = true;
+
+/**
+ * When disabled, secret files are never written to disk; their content lives in
+ * memory for the rest of the session and reads are served from there
+ */
+export function setPersistSecrets(enabled: boolean): void {
+    persistSecrets = enabled;
+}
+
+function keepInMemory(filePath: string, content: string): boolean {
+    if (persistSecrets || !SECRET_FILES.includes(path.basename(filePath))) {
+        // A disk write supersedes any copy kept while persistence was off
+        memorySecrets.delete(filePath);
+        return false;
+    }
+    memorySecrets.set(filePath, content);
+    console.warn(`[FileUtils] Not writing ${path.basename(filePath)} to disk (persistSecrets is off); kept in memory for this session`);
+    return true;
+}
+
+/**
+ * Error codes Windows reports while an antivirus or indexer briefly holds a file
+ */
+const TRANSIENT_FS_ERRORS = new Set(['EPERM', 'EACCES', 'EBUSY']);
+
+/**
+ * Run a sync fs operation, retrying a few times while it fails with a transient
+ * lock error. Other errors are thrown right away. Retries are immediate: a sleep
+ * here would block the Electron main process (UI and IPC) in between.
+ */
+export function withFsRetry<T>(op: () => T, attempts: number = 3): T {
+    for (let attempt = 1; ; attempt++) {
+        try {
+            return op();
+        } catch (error) {
+            const code = (error as NodeJS.ErrnoException)?.code;
+            if (attempt >= attempts || !code || !TRANSIENT_FS_ERRORS.has(code)) throw error;
+        }
+    }
+}
+
+function readContent(filePath: string): string | null {
+    const inMemory = memorySecrets.get(filePath);
+    if (inMemory !== undefined) return inMemory;
+    return fs.existsSync(filePath) ? fs.readFileSync(filePath, 'utf-8') : null;
+}
+
 /**
  * File utilities to avoid repeated file operations
  */
@@ -40,10 +140,11 @@ export const FileUtils = {
      * Read JSON file safely
      */
     readJson<T extends JsonData>(filename: string, defaultData: T): T {
-        const filePath = resolveAppPath(filename);
-        if (fs.existsSync(filePath)) {
+        const filePath = resolveDataPath(filename);
+        const content = readContent(filePath);
+        if (content !== null) {
             try {
-                const data = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+                const data = JSON.parse(content);
                 if (typeof data === 'object' && data !== null && !Array.isArray(data)) {
                     return { ...defaultData, ...data };
                 }
@@ -59,10 +160,11 @@ export const FileUtils = {
      * Read JSON array file safely
      */
     readJsonArray<T extends JsonArray>(filename: string, defaultData: T): T {
-        const filePath = resolveAppPath(filename);
-        if (fs.existsSync(filePath)) {
+        const filePath = resolveDataPath(filename);
+        const content = readContent(filePath);
+        if (content !== null) {
             try {
-                const data = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+                const data = JSON.parse(content);
                 if (Array.isArray(data)) {
                     return data as T;
                 }
@@ -77,9 +179,11 @@ export const FileUtils = {
      * Write JSON file safely
      */
     writeJson(filename: string, data: JsonValue): void {
-        const filePath = resolveAppPath(filename);
+        const filePath = resolveDataPath(filename);
+        const content = JSON.stringify(data, null, 2);
+        if (keepInMemory(filePath, content)) return;
         try {
-            fs.writeFileSync(filePath, JSON.stringify(data, null, 2));
+            withFsRetry(() => fs.writeFileSync(filePath, content));
         } catch (error) {
             console.error(`[FileUtils] Failed to write ${filename}:`, error);
         }
@@ -89,21 +193,19 @@ export const FileUtils = {
      * Check if file exists
      */
     exists(filename: string): boolean {
-        const filePath = resolveAppPath(filename);
-        return fs.existsSync(filePath);
+        const filePath = resolveDataPath(filename);
+        return memorySecrets.has(filePath) || fs.existsSync(filePath);
     },
 
     /**
      * Read file content
      */
     read(filename: string): string | null {
-        const filePath = resolveAppPath(filename);
-        if (fs.existsSync(filePath)) {
-            try {
-                return fs.readFileSync(filePath, 'utf-8');
-            } catch (error) {
-                console.error(`[FileUtils] Failed to read ${filename}:`, error);
-            }
+        const filePath = resolveDataPath(filename);
+        try {
+            return readContent(filePath);
+        } catch (error) {
+            console.error(`[FileUtils] Failed to read ${filename}:`, error);
         }
         return null;
     },
@@ -112,9 +214,10 @@ export const FileUtils = {
      * Write file content
      */
     write(filename: string, content: string): void {
-        const filePath = resolveAppPath(filename);
+        const filePath = resolveDataPath(filename);
+        if (keepInMemory(filePath, content)) return;
         try {
-            fs.writeFileSync(filePath, content);
+            withFsRetry(() => fs.writeFileSync(filePath, content));
         } catch (error) {
             console.error(`[FileUtils] Failed to write ${filename}:`, error);
         }
@@ -122,32 +225,159 @@ export const FileUtils = {
 };
 
 /**
- * Token storage utility
+ * Extract the OAuth token from any known Streamlabs response shape:
+ * top-level `oauth_token`, or `data.oauth_token` / `data.access_token`
+ */
+export function extractOauthToken(payload: unknown): string | null {
+    return tryParseTokenResponse(payload)?.oauthToken ?? null;
+}
+
+/**
+ * Unwrap the token-bearing object from an auth response (the object the token
+ * was found in). Returns null when the response failed or carries no token.
+ */
+export function extractAuthData(payload: unknown): Record<string, unknown> | null {
+    return tryParseTokenResponse(payload)?.data ?? null;
+}
+
+/**
+ * Normalize `expires_at` (seconds/ms epoch or date string) or `expires_in` (seconds)
+ * into an absolute ISO `expires_at`. Data without either is returned unchanged.
+ */
+export function withTokenExpiry(data: Record<string, unknown>, now: number = Date.now()): Record<string, unknown> {
+    const { expires_at: expiresAt, expires_in: expiresIn } = data;
+    let expiry: Date | null = null;
+
+    if (typeof expiresAt === 'number') {
+        // Epoch seconds unless the value is clearly in milliseconds
+        expiry = new Date(expiresAt < 1e12 ? expiresAt * 1000 : expiresAt);
+    } else if (typeof expiresAt === 'string') {
+        expiry = new Date(expiresAt);
+    } else if (typeof expiresIn === 'number' || (typeof expiresIn === 'string' && expiresIn.trim() !== '')) {
+        expiry = new Date(now + Number(expiresIn) * 1000);
+    }
+
+    if (!expiry || Number.isNaN(expiry.getTime())) return data;
+    return { ...data, expires_at: expiry.toISOString() };
+}
+
+/**
+ * Raised when a saved credential file does not match its checksum, or the
+ * credential directory cannot be used
+ */
+export class CredentialError extends Error {
+    constructor(public readonly kind: 'invalid_format' | 'invalid_dir', message: string) {
+        super(message);
+        this.name = 'CredentialError';
+    }
+}
+
+function withoutChecksum(data: JsonData): JsonData {
+    const { checksum: _checksum, ...payload } = data;
+    return payload;
+}
+
+/**
+ * SHA-256 (hex) of the serialized payload, excluding its own `checksum` field
+ */
+export function credentialChecksum(data: JsonData): string {
+    return crypto.createHash('sha256').update(JSON.stringify(withoutChecksum(data))).digest('hex');
+}
+
+/**
+ * Layout version of the saved credential files; bump when it changes
+ * (2: tokens.json carries a checksum)
+ */
+export const CREDENTIAL_FORMAT_VERSION = 2;
+
+const warnedLegacy = new Set<string>();
+
+/**
+ * Token storage utility. tokens.json carries a `checksum` of its content so
+ * corruption or hand edits are detected on load.
  */
 export class TokenStorage {
     private tokenPath: string;
 
     constructor(filename: string = 'tokens.json') {
-        this.tokenPath = resolveAppPath(filename);
+        this.tokenPath = resolveDataPath(filename);
     }
 
-    get(): string | null {
-        if (fs.existsSync(this.tokenPath)) {
-            try {
-                const data = JSON.parse(fs.readFileSync(this.tokenPath, 'utf-8'));
-                return data.oauth_token || null;
-            } catch (error) {
-                console.error('[TokenStorage] Failed to load tokens:', error);
+    /**
+     * Saved token data without its checksum, or null when there is none.
+     * Throws CredentialError on a checksum mismatch; files written before
+     * checksums existed are accepted with a warning.
+     */
+    load(): JsonData | null {
+        const content = readContent(this.tokenPath);
+        if (content === null) return null;
+
+        const data = JSON.parse(content);
+        if (typeof data !== 'object' || data === null || Array.isArray(data)) {
+            throw new CredentialError('invalid_format', `[TokenStorage] ${path.basename(this.tokenPath)} is not a JSON object`);
+        }
+        if (typeof data.checksum !== 'string') {
+            if (!warnedLegacy.has(this.tokenPath)) {
+                warnedLegacy.add(this.tokenPath);
+                console.warn(`[TokenStorage] ${path.basename(this.tokenPath)} has no checksum; it will be added on the next save`);
             }
+            return data;
+        }
+        if (data.checksum !== credentialChecksum(data)) {
+            throw new CredentialError('invalid_format', `[TokenStorage] ${path.basename(this.tokenPath)} failed its checksum (corrupted or edited)`);
+        }
+        return withoutChecksum(data);
+    }
+
+    get(): string | null {
+        try {
+            return extractOauthToken(this.load());
+        } catch (error) {
+            console.error('[TokenStorage] Failed to load tokens:', error);
         }
         return null;
     }
 
+    has(): boolean {
+        return this.get() !== null;
+    }
+
+    /**
+     * Expiry saved with the token, or null when the response had none
+     */
+    getExpiresAt(): Date | null {
+        const data = this.loadOrEmpty();
+        if (typeof data.expires_at !== 'string') return null;
+        const expiry = new Date(data.expires_at);
+        return Number.isNaN(expiry.getTime()) ? null : expiry;
+    }
+
+    getRefreshToken(): string | null {
+        const data = this.loadOrEmpty();
+        return typeof data.refresh_token === 'string' && data.refresh_token ? data.refresh_token : null;
+    }
+
     save(data: Record<string, unknown>): void {
-        FileUtils.writeJson(this.tokenPath, data);
+        const payload = withTokenExpiry(withoutChecksum(data));
+        FileUtils.writeJson(this.tokenPath, { ...payload, checksum: credentialChecksum(payload) });
+    }
+
+    private loadOrEmpty(): JsonData {
+        try {
+            return this.load() ?? {};
+        } catch {
+            return {};
+        }
     }
 }
 
+/**
+ * Check whether a Streamlabs token is available in memory or in tokens.json
+ */
+export function hasStreamlabsToken(inMemoryToken?: string | null, filename: string = 'tokens.json'): boolean {
+    return Boolean(inMemoryToken) || new TokenStorage(filename).has();
+}
+
 /**
  * Config storage utility
  */
@@ -170,3 +400,53 @@ export class ConfigStorage<T extends JsonData = Record<string, unknown>> {
         FileUtils.writeJson(this.configPath, merged);
     }
 }
+
+/**
+ * Files the app persists that support may inspect or wipe
+ */
+export const CREDENTIAL_FILES = ['cookies.json', 'tokens.json', 'user_info.json', 'config.json'] as const;
+export type CredentialFileName = typeof CREDENTIAL_FILES[number];
+
+export interface CredentialFileInfo {
+    name: CredentialFileName;
+    exists: boolean;
+    size?: number;
+    modifiedAt?: string;
+    /** Held in memory for this session only (persistSecrets is off); no file on disk */
+    inMemory?: boolean;
+}
+
+/**
+ * Report which credential files exist, with their size and modification time.
+ * Secrets kept in memory while persistSecrets is off are listed as existing
+ * with `inMemory: true`, since that copy is the one in use.
+ */
+export function listCredentialFiles(): CredentialFileInfo[] {
+    return CREDENTIAL_FILES.map(name => {
+        const filePath = resolveDataPath(name);
+        const inMemory = memorySecrets.get(filePath);
+        if (inMemory !== undefined) {
+            return { name, exists: true, inMemory: true, size: Buffer.byteLength(inMemory, 'utf-8') };
+        }
+        if (!fs.existsSync(filePath)) {
+            return { name, exists: false };
+        }
+        const stat = fs.statSync(filePath);
+        return { name, exists: true, size: stat.size, modifiedAt: stat.mtime.toISOString() };
+    });
+}
+
+/**
+ * Delete a credential file. Only names from CREDENTIAL_FILES are accepted,
+ * so callers cannot reach arbitrary paths.
+ */
+export function deleteCredentialFile(name: string): boolean {
+    if (!(CREDENTIAL_FILES as readonly string[]).includes(name)) {
+        throw new Error(`[FileUtils] Not a credential file: ${name}`);
+    }
+    const filePath = resolveDataPath(name);
+    const inMemory = memorySecrets.delete(filePath);
+    if (!fs.existsSync(filePath)) return inMemory;
+    withFsRetry(() => fs.unlinkSync(filePath));
+    return true;
+}
@@ -0,0 +1,30 @@
+import axios, { type AxiosInstance } from 'axios';
+import { HTTP_CONFIG, USER_AGENT } from '../constants';
+
+export interface ClientOptions {
+    baseURL?: string;
+    token?: string;
+    userAgent?: string;
+    timeoutMs?: number;
+}
+
+/**
+ * Default headers shared by every outgoing request (axios or fetch)
+ */
+export function buildHeaders(opts: ClientOptions = {}): Record<string, string> {
+    return {
+        'User-Agent': opts.userAgent || USER_AGENT,
+        ...(opts.token && { 'Authorization': `Bearer ${opts.token}` }),
+    };
+}
+
+/**
+ * Create an axios client with the app's user-agent and timeout defaults
+ */
+export function createHttpClient(opts: ClientOptions = {}): AxiosInstance {
+    return axios.create({
+        baseURL: opts.baseURL,
+        timeout: opts.timeoutMs ?? HTTP_CONFIG.TIMEOUT_MS,
+        headers: buildHeaders(opts),
+    });
+}
@@ -28,12 +28,14 @@ export function createIpcHandler<T>(
                     return null;
                 }
             }
-            return await handler(...args);
+            return await handler(event, ...args);
         } catch (error: any) {
             console.error(`[IPC Handler] ${channel}:`, error);
             return {
                 success: false,
                 error: error.message || ERROR_MESSAGES.AUTH_FAILED,
+                // ApiError/AuthError/StreamError kinds let the UI react to the failure type
+                ...(typeof error?.kind === 'string' && { kind: error.kind }),
             };
         }
     });
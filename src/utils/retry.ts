@@ -0,0 +1,68 @@
+import type { PollingOptions } from '../constants';
+import { sleep } from './apiClient';
+
+/**
+ * Attempts and exponential backoff (doubling, capped at maxDelayMs)
+ */
+export type RetryPolicy = PollingOptions;
+
+export interface RetryOptions<T> {
+    /** Retry when the operation resolves with a result that is not ready yet */
+    retryOnResult?: (result: T, attempt: number) => boolean;
+    /** Retry when the operation throws; errors are rethrown by default */
+    retryOnError?: (error: unknown, attempt: number) => boolean;
+    /** Stops further attempts and interrupts the backoff wait; rejects with the abort reason */
+    signal?: AbortSignal;
+}
+
+export interface RetryOutcome<T> {
+    result: T;
+    attempts: number;
+}
+
+function waitFor(ms: number, signal?: AbortSignal): Promise<void> {
+    if (!signal) return sleep(ms);
+    return new Promise((resolve, reject) => {
+        if (signal.aborted) return reject(signal.reason);
+        const onAbort = () => {
+            clearTimeout(timer);
+            reject(signal.reason);
+        };
+        const timer = setTimeout(() => {
+            signal.removeEventListener('abort', onAbort);
+            resolve();
+        }, ms);
+        signal.addEventListener('abort', onAbort, { once: true });
+    });
+}
+
+/**
+ * Run `op` until it succeeds or the policy's attempts are used up.
+ * When attempts run out, the last result is returned (callers decide whether it
+ * is still pending) and the last error is rethrown.
+ */
+export async function retry<T>(
+    policy: RetryPolicy,
+    op: (attempt: number) => Promise<T>,
+    options: RetryOptions<T> = {}
+): Promise<RetryOutcome<T>> {
+    let delay = policy.initialDelayMs;
+    const { signal } = options;
+    for (let attempt = 1; ; attempt++) {
+        signal?.throwIfAborted();
+        const isLast = attempt >= policy.maxAttempts;
+        try {
+            const result = await op(attempt);
+            signal?.throwIfAborted();
+            if (isLast || !options.retryOnResult?.(result, attempt)) {
+                return { result, attempts: attempt };
+            }
+        } catch (error) {
+            if (isLast || signal?.aborted || !options.retryOnError?.(error, attempt)) {
+                throw error;
+            }
+        }
+        await waitFor(delay, signal);
+        delay = Math.min(delay * 2, policy.maxDelayMs);
+    }
+}
@@ -0,0 +1,27 @@
+/**
+ * String helpers that count and cut in Unicode code points, so an emoji or
+ * other astral character is never split into a lone surrogate.
+ */
+
+export function codePointLength(text: string): number {
+    let length = 0;
+    for (const _ of text) length++;
+    return length;
+}
+
+/**
+ * `String.prototype.slice` in code points: negative indices count from the end
+ * and out-of-range indices are clamped instead of failing
+ */
+export function safeSlice(text: string, start: number, end?: number): string {
+    return Array.from(text).slice(start, end).join('');
+}
+
+/**
+ * Single-line form text (e.g. stream titles): control characters such as
+ * newlines and tabs become spaces, runs of whitespace collapse to one space and
+ * the ends are trimmed. Other Unicode (emoji, ZWJ sequences, CJK) is kept.
+ */
+export function sanitizeText(text: string): string {
+    return text.replace(/\p{Cc}/gu, ' ').replace(/\s+/g, ' ').trim();
+}
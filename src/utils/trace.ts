@@ -0,0 +1,58 @@
+/**
+ * Minimal structured spans for following multi-step flows (login, token exchange)
+ * in the logs. Field values must never contain tokens, codes or cookies.
+ */
+export type SpanFieldValue = string | number | boolean;
+
+export interface SpanRecord {
+    name: string;
+    fields: Record<string, SpanFieldValue>;
+    durationMs: number;
+}
+
+export type SpanSink = (span: SpanRecord) => void;
+
+const consoleSink: SpanSink = span => {
+    console.log(`[Trace] ${span.name} (${span.durationMs}ms)`, JSON.stringify(span.fields));
+};
+
+let sink: SpanSink = consoleSink;
+
+/**
+ * Replace where finished spans are sent. Returns a function restoring the previous sink.
+ */
+export function setSpanSink(next: SpanSink): () => void {
+    const previous = sink;
+    sink = next;
+    return () => {
+        sink = previous;
+    };
+}
+
+export class Span {
+    private readonly startedAt = Date.now();
+    private readonly fields: Record<string, SpanFieldValue>;
+    private ended = false;
+
+    constructor(public readonly name: string, fields: Record<string, SpanFieldValue> = {}) {
+        this.fields = { ...fields };
+    }
+
+    record(key: string, value: SpanFieldValue): this {
+        this.fields[key] = value;
+        return this;
+    }
+
+    /**
+     * Finish the span and send it to the sink; later calls are ignored
+     */
+    end(): void {
+        if (this.ended) return;
+        this.ended = true;
+        sink({ name: this.name, fields: { ...this.fields }, durationMs: Date.now() - this.startedAt });
+    }
+}
+
+export function startSpan(name: string, fields?: Record<string, SpanFieldValue>): Span {
+    return new Span(name, fields);
+}
@@ -0,0 +1,22 @@
+import packageJson from '../../package.json';
+import { CREDENTIAL_FORMAT_VERSION } from './fileUtils';
+
+export interface VersionInfo {
+    appVersion: string;
+    /** Null when not running under Electron (e.g. tests) */
+    electronVersion: string | null;
+    nodeVersion: string;
+    credentialFormatVersion: number;
+}
+
+/**
+ * Versions to attach to bug reports
+ */
+export function getVersionInfo(): VersionInfo {
+    return {
+        appVersion: packageJson.version,
+        electronVersion: process.versions.electron ?? null,
+        nodeVersion: process.versions.node,
+        credentialFormatVersion: CREDENTIAL_FORMAT_VERSION,
+    };
+}
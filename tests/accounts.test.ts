@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('accounts');
+
+const { listSavedAccounts } = await import('../src/auth/accounts');
+
+function writeAccount(id: string, files: Record<string, unknown>) {
+    const dir = path.join(tmpDir, 'accounts', id);
+    fs.mkdirSync(dir, { recursive: true });
+    for (const [name, content] of Object.entries(files)) {
+        fs.writeFileSync(path.join(dir, name), JSON.stringify(content));
+    }
+    return dir;
+}
+
+describe('listSavedAccounts', () => {
+    it('should list every account directory with its id and captured user', () => {
+        const mainDir = writeAccount('main', {
+            'cookies.json': [{ name: 'multi_sids', value: '7001%3Asess', domain: '.tiktok.com', path: '/', secure: true, httpOnly: true }],
+            'tokens.json': { oauth_token: 'main-token' },
+        });
+        const altDir = writeAccount('alt', { 'tokens.json': { oauth_token: 'alt-token' } });
+        fs.mkdirSync(path.join(tmpDir, 'accounts', 'empty'), { recursive: true });
+
+        expect(listSavedAccounts()).toEqual([
+            { id: 'alt', dir: altDir, hasToken: true, hasCookies: false },
+            { id: 'main', dir: mainDir, userId: '7001', hasToken: true, hasCookies: true },
+        ]);
+    });
+
+    it('should name an account from its captured user info', () => {
+        const dir = writeAccount('named', {
+            'tokens.json': { oauth_token: 'named-token' },
+            'user_info.json': { uid: '7002', uniqueId: 'streamer', nickname: 'The Streamer' },
+        });
+
+        expect(listSavedAccounts().find(account => account.id === 'named')).toEqual({
+            id: 'named', dir, userId: '7002', username: 'streamer', nickname: 'The Streamer', hasToken: true, hasCookies: false,
+        });
+    });
+
+    it('should return an empty list when the accounts folder is missing', () => {
+        expect(listSavedAccounts(path.join(tmpDir, 'missing'))).toEqual([]);
+    });
+});
@@ -1,17 +1,22 @@
-import { describe, it, expect, mock, beforeAll } from 'bun:test';
-import { StreamAPI } from '../src/api/StreamAPI';
+import { describe, it, expect, mock, spyOn, beforeAll } from 'bun:test';
+import { StreamAPI, devicePlatform, maskStreamKey, parseStreamHistory, parseAudienceTypes } from '../src/api/StreamAPI';
+import { ApiError } from '../src/utils/apiClient';
+import { createHttpClient } from '../src/utils/http';
 
 // Mock axios
 const mockPost = mock(() => Promise.resolve({ data: { rtmp: 'rtmp://test', key: 'key123', id: '123' } }));
 const mockGet = mock(() => Promise.resolve({ data: { categories: [{ id: '1', full_name: 'Test Game', game_mask_id: '100' }] } }));
 
+const mockCreate = mock((config?: any) => ({
+    post: mockPost,
+    get: mockGet,
+    defaults: { headers: { ...config?.headers } },
+}));
+
 mock.module('axios', () => {
     return {
         default: {
-            create: () => ({
-                post: mockPost,
-                get: mockGet
-            })
+            create: mockCreate
         }
     };
 });
@@ -33,4 +38,252 @@ describe('StreamAPI', () => {
         expect(result?.streamKey).toBe('key123');
         expect(mockPost).toHaveBeenCalled();
     });
+
+    it('should return a full user profile', async () => {
+        mockGet.mockImplementationOnce(() => Promise.resolve({
+            data: { user: { username: 'streamer', avatar_url: 'https://a/full.png', avatar_thumb: 'https://a/thumb.png' } }
+        }) as any);
+        const profile = await api.getUserProfile();
+        expect(profile?.username).toBe('streamer');
+        expect(profile?.avatar_url).toBe('https://a/full.png');
+        expect(profile?.avatar_thumb).toBe('https://a/thumb.png');
+    });
+
+    it('should fall back to the thumbnail when avatar_url is missing', async () => {
+        mockGet.mockImplementationOnce(() => Promise.resolve({
+            data: { user: { username: 'streamer', avatar_thumb: 'https://a/thumb.png' } }
+        }) as any);
+        const profile = await new StreamAPI('fake-token').getUserProfile();
+        expect(profile?.avatar_url).toBe('https://a/thumb.png');
+    });
+
+    it('should raise an unauthorized error on 401', async () => {
+        mockGet.mockImplementationOnce(() => Promise.reject({ message: 'Request failed', response: { status: 401 } }));
+        const error = await new StreamAPI('fake-token').getUserProfile().catch(e => e);
+        expect(error).toBeInstanceOf(ApiError);
+        expect(error.kind).toBe('unauthorized');
+    });
+
+    it('should send requests to an overridden base URL', async () => {
+        const mockApi = StreamAPI.withBaseUrl('fake-token', 'http://localhost:9999/tiktok');
+        await mockApi.getInfo();
+
+        expect(mockCreate.mock.calls.at(-1)?.[0]?.baseURL).toBe('http://localhost:9999/tiktok');
+        expect((mockGet.mock.calls.at(-1) as unknown[] | undefined)?.[0]).toBe('/info');
+    });
+
+    it('should prefer rtmps from a nested start response', async () => {
+        mockPost.mockImplementationOnce(() => Promise.resolve({
+            data: { stream: { id: '456', rtmp: 'rtmp://plain', rtmps: { url: 'rtmps://secure', key: 'nestedkey' } } }
+        }) as any);
+        const result = await api.start('My Stream', '1');
+        expect(result).toEqual({ rtmpUrl: 'rtmps://secure', rtmpsUrl: 'rtmps://secure', streamKey: 'nestedkey', id: '456' });
+    });
+
+    it('should raise missing_data when the start response has no ingest info', async () => {
+        mockPost.mockImplementationOnce(() => Promise.resolve({ data: { id: '789' } }) as any);
+        const error = await api.start('My Stream', '1').catch(e => e);
+        expect(error).toBeInstanceOf(ApiError);
+        expect(error.kind).toBe('missing_data');
+    });
+
+    it('should adopt the id of a live stream from /stream/current', async () => {
+        const freshApi = new StreamAPI('fake-token');
+        mockGet.mockImplementationOnce(() => Promise.resolve({
+            data: { stream: { id: 'live-1', rtmp: 'rtmp://live', key: 'livekey' } }
+        }) as any);
+
+        const current = await freshApi.getCurrentStream();
+
+        expect(current).toEqual({ rtmpUrl: 'rtmp://live', streamKey: 'livekey', id: 'live-1' });
+        expect(freshApi.getCurrentStreamId()).toBe('live-1');
+    });
+
+    it('should report no current stream only on a definite answer', async () => {
+        const freshApi = new StreamAPI('fake-token');
+        mockGet.mockImplementationOnce(() => Promise.reject({ message: 'Not Found', response: { status: 404 } }));
+        expect(await freshApi.getCurrentStream()).toBeNull();
+
+        mockGet.mockImplementationOnce(() => Promise.reject({ message: 'Network Error' }));
+        const error = await freshApi.getCurrentStream().catch(e => e);
+        expect(error).toBeInstanceOf(ApiError);
+        expect(error.kind).toBe('network');
+    });
+
+    it('should forget the stream id once the stream has ended', async () => {
+        const freshApi = new StreamAPI('fake-token');
+        await freshApi.start('My Stream', '1');
+        expect(freshApi.getCurrentStreamId()).toBe('123');
+
+        mockPost.mockImplementationOnce(() => Promise.resolve({ data: { success: true } }) as any);
+        expect(await freshApi.end()).toBe(true);
+
+        // APP_STATUS derives streamLive from this id
+        expect(freshApi.getCurrentStreamId()).toBeNull();
+    });
+
+    it('should keep the stream id when ending fails', async () => {
+        const freshApi = new StreamAPI('fake-token');
+        await freshApi.start('My Stream', '1');
+
+        mockPost.mockImplementationOnce(() => Promise.resolve({ data: { success: false } }) as any);
+        expect(await freshApi.end()).toBe(false);
+
+        expect(freshApi.getCurrentStreamId()).toBe('123');
+    });
+
+    it('should encode spaces but keep dashes and underscores literal', async () => {
+        await api.search('  Grand Theft-Auto_V  ');
+        expect((mockGet.mock.calls.at(-1) as unknown[] | undefined)?.[0]).toBe('/info?category=Grand%20Theft-Auto_V');
+    });
+
+    it('should load initial categories for a whitespace-only query', async () => {
+        await api.search('   ');
+        expect((mockGet.mock.calls.at(-1) as unknown[] | undefined)?.[0]).toBe('/info?category=gaming');
+    });
+
+    it('should apply the configured user-agent in the client factory', () => {
+        createHttpClient({ userAgent: 'Custom/1.0', token: 'abc' });
+        const config = mockCreate.mock.calls.at(-1)?.[0];
+        expect(config.headers['User-Agent']).toBe('Custom/1.0');
+        expect(config.headers['Authorization']).toBe('Bearer abc');
+    });
+
+    it('should send the new bearer token after setToken', async () => {
+        const rotated = new StreamAPI('old-token');
+        mockPost.mockImplementationOnce(() => Promise.resolve({ data: { rtmp: 'rtmp://x', key: 'k', id: '9' } }));
+        await rotated.start('Title', '1');
+
+        rotated.setToken('new-token');
+        expect(rotated.getClient().defaults.headers['Authorization']).toBe('Bearer new-token');
+        expect(rotated.getCurrentStreamId()).toBe('9');
+
+        rotated.clearToken();
+        expect(rotated.getClient().defaults.headers['Authorization']).toBeUndefined();
+    });
+
+    it('should send the device platform of the current OS', async () => {
+        mockPost.mockImplementationOnce(() => Promise.resolve({ data: { rtmp: 'rtmp://x', key: 'k', id: '1' } }));
+        await new StreamAPI('token').start('Title', '1');
+        const form = (mockPost.mock.calls.at(-1) as unknown[] | undefined)?.[1] as FormData;
+        expect(form.get('device_platform')).toBe(devicePlatform());
+    });
+
+    it('should map each OS to a Streamlabs device platform', () => {
+        expect(devicePlatform('win32')).toBe('win32');
+        expect(devicePlatform('darwin')).toBe('darwin');
+        expect(devicePlatform('linux')).toBe('linux');
+        expect(devicePlatform('freebsd')).toBe('linux');
+    });
+
+    it('should apply a user-agent override', () => {
+        new StreamAPI('token', undefined, 'Custom/2.0');
+        expect(mockCreate.mock.calls.at(-1)?.[0]?.headers['User-Agent']).toBe('Custom/2.0');
+    });
+
+    it('should dedupe and sort search results', async () => {
+        mockGet.mockImplementationOnce(() => Promise.resolve({
+            data: {
+                categories: [
+                    { id: '3', full_name: 'minecraft', game_mask_id: 'm' },
+                    { id: '1', full_name: 'Apex Legends', game_mask_id: 'a' },
+                    { id: '4', full_name: 'Minecraft Dungeons', game_mask_id: 'd' },
+                    { id: '2', full_name: 'Apex Legends (dup)', game_mask_id: 'a' },
+                ],
+            },
+        }) as any);
+
+        const results = await api.search('game');
+        expect(results.map(c => c.id)).toEqual(['1', '3', '4']);
+    });
+
+    it('should serve a fresh profile from the cache until the token changes', async () => {
+        const cachedApi = new StreamAPI('fake-token');
+        mockGet.mockImplementation(() => Promise.resolve({ data: { user: { username: 'cached' } } }) as any);
+        try {
+            await cachedApi.getUserProfile();
+            const calls = mockGet.mock.calls.length;
+
+            expect((await cachedApi.getUserProfile())?.username).toBe('cached');
+            expect(mockGet.mock.calls.length).toBe(calls);
+
+            cachedApi.setToken('rotated');
+            await cachedApi.getUserProfile();
+            expect(mockGet.mock.calls.length).toBe(calls + 1);
+        } finally {
+            mockGet.mockImplementation(() => Promise.resolve({ data: { categories: [{ id: '1', full_name: 'Test Game', game_mask_id: '100' }] } }));
+        }
+    });
+
+    it('should populate rtmpsUrl from an rtmps_url field', async () => {
+        mockPost.mockImplementationOnce(() => Promise.resolve({
+            data: { id: '7', rtmp: 'rtmp://plain', rtmps_url: 'rtmps://secure', key: 'k' }
+        }) as any);
+        const result = await api.start('My Stream', '1');
+        expect(result?.rtmpsUrl).toBe('rtmps://secure');
+        expect(result?.rtmpUrl).toBe('rtmps://secure');
+    });
+
+    it('should never log the full stream key', async () => {
+        const logged: string[] = [];
+        const logSpy = spyOn(console, 'log').mockImplementation((...args: unknown[]) => { logged.push(args.join(' ')); });
+        const errorSpy = spyOn(console, 'error').mockImplementation((...args: unknown[]) => { logged.push(args.join(' ')); });
+        try {
+            mockPost.mockImplementationOnce(() => Promise.resolve({ data: { id: '1', rtmp: 'rtmp://x', key: 'super-secret-key' } }) as any);
+            const info = await api.start('My Stream', '1');
+            mockPost.mockImplementationOnce(() => Promise.resolve({ data: { key: 'super-secret-key' } }) as any);
+            await api.start('My Stream', '1').catch(() => {});
+
+            expect(info?.streamKey).toBe('super-secret-key');
+            expect(logged.join('\n')).toContain(maskStreamKey('super-secret-key'));
+            expect(logged.join('\n')).not.toContain('super-secret-key');
+        } finally {
+            logSpy.mockRestore();
+            errorSpy.mockRestore();
+        }
+    });
+});
+
+describe('parseStreamHistory', () => {
+    it('should map a mock list response into summaries', () => {
+        const body = {
+            streams: [
+                { id: 42, title: 'Late night', started_at: '2024-05-01T20:00:00Z', ended_at: 1714597200, peak_viewers: 130 },
+                { stream_id: 'live-1', title: 'Now', start_time: 1714600000, max_viewers: '7' },
+                { title: 'no id' },
+            ],
+        };
+
+        expect(parseStreamHistory(body)).toEqual([
+            { id: '42', title: 'Late night', startedAt: '2024-05-01T20:00:00.000Z', endedAt: '2024-05-01T21:00:00.000Z', peakViewers: 130 },
+            { id: 'live-1', title: 'Now', startedAt: '2024-05-01T21:46:40.000Z', endedAt: null, peakViewers: 7 },
+        ]);
+    });
+
+    it('should return an empty list for unexpected shapes', () => {
+        expect(parseStreamHistory(null)).toEqual([]);
+        expect(parseStreamHistory({ streams: 'nope' })).toEqual([]);
+    });
 });
+
+describe('audience types', () => {
+    it('should map a mock options response', () => {
+        const body = { audience_types: [{ id: 0, label: 'Everyone' }, { value: '2', name: 'Friends' }, { id: 3 }] };
+        expect(parseAudienceTypes(body)).toEqual([
+            { id: '0', label: 'Everyone' },
+            { id: '2', label: 'Friends' },
+        ]);
+    });
+
+    it('should fall back to the default list when /info fails', async () => {
+        const warnSpy = spyOn(console, 'warn').mockImplementation(() => {});
+        try {
+            mockGet.mockImplementationOnce(() => Promise.reject(new Error('offline')));
+            const types = await new StreamAPI('fake-token').getAudienceTypes();
+            expect(types.map(type => type.id)).toEqual(['0', '1']);
+        } finally {
+            warnSpy.mockRestore();
+        }
+    });
+});
+
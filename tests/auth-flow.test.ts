@@ -0,0 +1,127 @@
+import { describe, it, expect, afterAll } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('auth-flow');
+
+const { AuthManager } = await import('../src/auth/AuthManager');
+const { AuthError } = await import('../src/auth/errors');
+
+let slowCalls = 0;
+const server = Bun.serve({
+    port: 0,
+    fetch(req) {
+        const code = new URL(req.url).searchParams.get('code');
+        if (code === 'cookie') return Response.json({ oauth_token: req.headers.get('cookie') ?? 'none' });
+        if (code === 'pending') return Response.json({ success: false });
+        if (code === 'flat') return Response.json({ oauth_token: 'flat-token' });
+        if (code === 'nested') return Response.json({ success: true, data: { oauth_token: 'nested-token' } });
+        if (code === 'slow') {
+            slowCalls += 1;
+            return slowCalls <= 2
+                ? Response.json({ success: false })
+                : Response.json({ success: true, data: { oauth_token: 'slow-token' } });
+        }
+        return new Response('boom', { status: 500 });
+    },
+});
+const mockBase = `http://localhost:${server.port}`;
+const createAuthManager = () => new AuthManager({
+    endpoints: { loginUrl: `${mockBase}/m/login`, authDataUrl: `${mockBase}/auth/data` },
+    polling: { initialDelayMs: 1, maxDelayMs: 5 },
+});
+const readSavedToken = () => JSON.parse(fs.readFileSync(path.join(tmpDir, 'tokens.json'), 'utf-8')).oauth_token;
+
+afterAll(() => server.stop());
+
+describe('AuthManager.exchangeCodeForToken', () => {
+    it('should build the auth URL from the configured endpoints', async () => {
+        const url = await createAuthManager().getAuthUrl();
+        expect(url.startsWith(`${mockBase}/m/login?`)).toBe(true);
+    });
+
+    it('should save a flat token response', async () => {
+        const token = await createAuthManager().exchangeCodeForToken('flat');
+        expect(token).toBe('flat-token');
+        expect(readSavedToken()).toBe('flat-token');
+    });
+
+    it('should save a data.oauth_token response', async () => {
+        const token = await createAuthManager().exchangeCodeForToken('nested');
+        expect(token).toBe('nested-token');
+        expect(readSavedToken()).toBe('nested-token');
+    });
+
+    it('should keep polling while auth data reports success:false', async () => {
+        const token = await createAuthManager().exchangeCodeForToken('slow');
+        expect(token).toBe('slow-token');
+        expect(slowCalls).toBe(3);
+    });
+
+    it('should send the captured session cookies with the request', async () => {
+        const cookiesPath = path.join(tmpDir, 'cookies.json');
+        fs.writeFileSync(cookiesPath, JSON.stringify([
+            { name: 'sessionid', value: 'sess', domain: 'localhost', path: '/', secure: false, httpOnly: true },
+            { name: 'other', value: 'x', domain: '.tiktok.com', path: '/', secure: true, httpOnly: false },
+        ]));
+        try {
+            expect(await createAuthManager().exchangeCodeForToken('cookie')).toBe('sessionid=sess');
+        } finally {
+            fs.rmSync(cookiesPath);
+        }
+    });
+
+    it('should reject an error status with an http AuthError', async () => {
+        const error = await createAuthManager().exchangeCodeForToken('error').catch(e => e);
+        expect(error).toBeInstanceOf(AuthError);
+        expect(error.message).toContain('HTTP 500');
+        expect(error.kind).toBe('http');
+        expect(error.status).toBe(500);
+    });
+
+    it('should raise a timeout AuthError when polling is exhausted', async () => {
+        const error = await createAuthManager().exchangeCodeForToken('pending').catch(e => e);
+        expect(error).toBeInstanceOf(AuthError);
+        expect(error.kind).toBe('timeout');
+    });
+});
+
+describe('AuthManager.submitAuthCode', () => {
+    it('should drive a pasted code through the exchange to success', async () => {
+        const token = await createAuthManager().submitAuthCode('  nested  ');
+        expect(token).toBe('nested-token');
+        expect(readSavedToken()).toBe('nested-token');
+    });
+
+    it('should reject empty and non URL-safe codes without a request', async () => {
+        for (const code of ['', '   ', 'abc def', 'abc&code=x']) {
+            const error = await createAuthManager().submitAuthCode(code).catch(e => e);
+            expect(error).toBeInstanceOf(AuthError);
+            expect(error.kind).toBe('invalid_code');
+        }
+    });
+});
+
+describe('AuthManager.getAuthMetrics', () => {
+    it('should record request and total durations for a mocked exchange', async () => {
+        const manager = createAuthManager();
+        expect(manager.getAuthMetrics()).toBeNull();
+
+        await manager.exchangeCodeForToken('flat');
+
+        const metrics = manager.getAuthMetrics();
+        expect(metrics?.requestDurationsMs).toHaveLength(1);
+        expect(metrics!.requestDurationsMs[0]).toBeGreaterThan(0);
+        expect(metrics!.totalDurationMs).toBeGreaterThan(0);
+    });
+
+    it('should keep request timings without a total when the exchange fails', async () => {
+        const manager = createAuthManager();
+        await manager.exchangeCodeForToken('error').catch(() => {});
+
+        expect(manager.getAuthMetrics()?.requestDurationsMs).toHaveLength(1);
+        expect(manager.getAuthMetrics()?.totalDurationMs).toBeNull();
+    });
+});
+
@@ -0,0 +1,99 @@
+import { describe, it, expect, beforeEach } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('auth-state');
+
+const { checkLoginState, validateToken, getAppStatus, resolveStartupToken } = await import('../src/auth/AuthManager');
+const { ConfigManager } = await import('../src/config/ConfigManager');
+const { ApiError } = await import('../src/utils/apiClient');
+
+describe('checkLoginState', () => {
+    beforeEach(() => {
+        fs.writeFileSync(path.join(tmpDir, 'cookies.json'), '[]');
+        fs.writeFileSync(path.join(tmpDir, 'tokens.json'), JSON.stringify({ oauth_token: 'saved' }));
+    });
+
+    it('should report a valid session when the API accepts the token', async () => {
+        const result = await checkLoginState({
+            validate: true,
+            createStreamApi: () => ({ getUserProfile: async () => ({ username: 'streamer' }) }),
+        });
+        expect(result.state).toBe('authenticated');
+    });
+
+    it('should report cookies_invalid when the API returns 401', async () => {
+        const result = await checkLoginState({
+            validate: true,
+            createStreamApi: () => ({
+                getUserProfile: async () => { throw new ApiError('unauthorized', 'Unauthorized', 401); },
+            }),
+        });
+        expect(result).toEqual({ state: 'cookies_invalid', reason: 'unauthorized' });
+    });
+});
+
+describe('validateToken', () => {
+    it('should map a profile response to a valid token', async () => {
+        const result = await validateToken('token', () => ({ getUserProfile: async () => ({ username: 'streamer' }) }));
+        expect(result).toEqual({ valid: true, username: 'streamer' });
+    });
+
+    it('should map a 401 to an unauthorized token', async () => {
+        const result = await validateToken('token', () => ({
+            getUserProfile: async () => { throw new ApiError('unauthorized', 'Unauthorized', 401); },
+        }));
+        expect(result).toEqual({ valid: false, reason: 'unauthorized' });
+    });
+});
+
+describe('getAppStatus', () => {
+    it('should report a fully authenticated state', () => {
+        fs.writeFileSync(path.join(tmpDir, 'cookies.json'), JSON.stringify([
+            { name: 'sessionid', value: 'abc', domain: '.tiktok.com', path: '/', secure: true, httpOnly: true },
+        ]));
+        fs.writeFileSync(path.join(tmpDir, 'tokens.json'), JSON.stringify({ oauth_token: 'saved', expires_at: '2030-01-01T00:00:00.000Z' }));
+
+        expect(getAppStatus({ streamLive: true, now: Date.parse('2029-01-01') })).toEqual({
+            tiktokCookies: true,
+            streamlabsToken: true,
+            tokenExpired: false,
+            streamLive: true,
+        });
+    });
+
+    it('should report an empty state', () => {
+        fs.rmSync(path.join(tmpDir, 'cookies.json'), { force: true });
+        fs.rmSync(path.join(tmpDir, 'tokens.json'), { force: true });
+
+        expect(getAppStatus()).toEqual({
+            tiktokCookies: false,
+            streamlabsToken: false,
+            tokenExpired: false,
+            streamLive: false,
+        });
+    });
+});
+
+describe('resolveStartupToken', () => {
+    beforeEach(() => {
+        fs.writeFileSync(path.join(tmpDir, 'tokens.json'), JSON.stringify({ oauth_token: 'saved' }));
+    });
+
+    it('should prefer STREAM_TOKEN over tokens.json', () => {
+        process.env.STREAM_TOKEN = 'from-env';
+        try {
+            const config = new ConfigManager('config.json');
+            config.load();
+            expect(resolveStartupToken(config.get('token'))).toEqual({ token: 'from-env', source: 'config' });
+        } finally {
+            delete process.env.STREAM_TOKEN;
+        }
+    });
+
+    it('should fall back to tokens.json without a config token', () => {
+        expect(resolveStartupToken(undefined)).toEqual({ token: 'saved', source: 'tokens' });
+        expect(resolveStartupToken('  ')).toEqual({ token: 'saved', source: 'tokens' });
+    });
+});
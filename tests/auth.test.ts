@@ -1,4 +1,5 @@
 import { describe, it, expect } from 'bun:test';
+import crypto from 'node:crypto';
 import { AuthManager } from '../src/auth/AuthManager';
 
 describe('AuthManager', () => {
@@ -25,4 +26,38 @@ describe('AuthManager', () => {
         expect(url).toContain('code_challenge=');
         expect(url).toContain('force_verify=1');
     });
+
+    it('should derive a stable challenge from a fixed verifier', async () => {
+        // RFC 7636 appendix B example pair
+        const fixed = AuthManager.withVerifier('dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk');
+        const url = new URL(await fixed.getAuthUrl());
+        expect(url.searchParams.get('code_challenge')).toBe('E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM');
+    });
+
+    it('should keep the historical challenge for a 128-char hex verifier', async () => {
+        const legacy = AuthManager.withVerifier('0123456789abcdef'.repeat(8));
+        const url = new URL(await legacy.getAuthUrl());
+        expect(url.searchParams.get('code_challenge')).toBe('syDoWXjbBRNAA6KRTuvd2NO4cmgY8uLGeeGJjHIVYqk');
+    });
+
+    it('should generate a fresh verifier on every PKCE reset', async () => {
+        const manager = AuthManager.withVerifier('fixed');
+        const first = manager.resetPkce();
+        const firstVerifier = (manager as any).codeVerifier;
+        const second = manager.resetPkce();
+
+        expect((manager as any).codeVerifier).not.toBe(firstVerifier);
+        expect(second).not.toBe(first);
+        expect(await manager.getAuthUrl()).toContain(`code_challenge=${second}`);
+    });
+
+    it('should build the prepared login URL from the stored verifier', async () => {
+        const manager = AuthManager.withVerifier('fixed');
+        const url = new URL(await manager.prepareLogin());
+        const verifier = (manager as any).codeVerifier;
+
+        const expected = crypto.createHash('sha256').update(verifier).digest('base64url');
+        expect(verifier).not.toBe('fixed');
+        expect(url.searchParams.get('code_challenge')).toBe(expected);
+    });
 });
@@ -0,0 +1,40 @@
+import { describe, it, expect } from 'bun:test';
+import { CaptureStats } from '../src/auth/captureStats';
+
+describe('CaptureStats', () => {
+    it('should return captures in order', () => {
+        const stats = new CaptureStats();
+        stats.record(3, false, 1000);
+        stats.record(10, false, 2000);
+        stats.record(12, true, 3000);
+
+        expect(stats.series()).toEqual([
+            { timestamp: 1000, totalItems: 3, loginDetected: false },
+            { timestamp: 2000, totalItems: 10, loginDetected: false },
+            { timestamp: 3000, totalItems: 12, loginDetected: true },
+        ]);
+    });
+
+    it('should drop the oldest samples past capacity', () => {
+        const stats = new CaptureStats(2);
+        stats.record(1, false, 1);
+        stats.record(2, false, 2);
+        stats.record(3, true, 3);
+
+        expect(stats.series().map(s => s.totalItems)).toEqual([2, 3]);
+    });
+});
+
+describe('CaptureStats.subscribe', () => {
+    it('should notify subscribers until they unsubscribe', async () => {
+        const stats = new CaptureStats();
+        const received: number[] = [];
+        const unsubscribe = stats.subscribe(sample => received.push(sample.totalItems));
+
+        await Promise.resolve().then(() => stats.record(5, true));
+        unsubscribe();
+        stats.record(6, true);
+
+        expect(received).toEqual([5]);
+    });
+});
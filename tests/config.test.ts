@@ -0,0 +1,133 @@
+import { describe, it, expect, mock, beforeEach } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('config');
+const configFile = path.join(tmpDir, 'config.json');
+
+const { ConfigManager, validateConfig, toRendererConfig } = await import('../src/config/ConfigManager');
+
+const readSaved = () => JSON.parse(fs.readFileSync(configFile, 'utf-8'));
+
+describe('ConfigManager', () => {
+    beforeEach(() => {
+        fs.writeFileSync(configFile, JSON.stringify({ title: 'Old Title', game: '100' }));
+    });
+
+    it('should overwrite an existing title with a new one', () => {
+        const config = new ConfigManager('config.json');
+        config.load();
+        config.save({ title: 'New Title' });
+
+        expect(readSaved().title).toBe('New Title');
+        expect(config.get('title')).toBe('New Title');
+    });
+
+    it('should not wipe existing values with undefined fields', () => {
+        const config = new ConfigManager('config.json');
+        config.load();
+        config.save({ title: undefined, game: '200' });
+
+        expect(readSaved().title).toBe('Old Title');
+        expect(readSaved().game).toBe('200');
+    });
+
+    it('should let environment variables override file values', () => {
+        process.env.STREAM_TITLE = 'Env Title';
+        try {
+            const config = new ConfigManager('config.json');
+            const loaded = config.load();
+
+            expect(loaded.title).toBe('Env Title');
+            expect(loaded.game).toBe('100');
+            expect(loaded.token).toBeUndefined();
+        } finally {
+            delete process.env.STREAM_TITLE;
+        }
+    });
+
+    it('should read boolean and previously missing string keys', () => {
+        fs.writeFileSync(configFile, JSON.stringify({ audienceType: '1', suppressDonationReminder: true }));
+        const config = new ConfigManager('config.json');
+        config.load();
+
+        expect(config.getBool('suppressDonationReminder')).toBe(true);
+        expect(config.get('audienceType')).toBe('1');
+        expect(config.getBool('title')).toBeUndefined();
+        expect(config.getAll().suppressDonationReminder).toBe(true);
+    });
+
+    it('should reload the config once for a burst of changes to the file', async () => {
+        const config = new ConfigManager('config.json');
+        config.load();
+
+        let reloads = 0;
+        let resolveReload: (title: string | undefined) => void = () => {};
+        const reloaded = new Promise<string | undefined>(resolve => { resolveReload = resolve; });
+        const handler = config.createChangeHandler(updated => {
+            reloads += 1;
+            resolveReload(updated.title);
+        }, 0);
+
+        fs.writeFileSync(configFile, JSON.stringify({ title: 'Edited Title' }));
+        handler.onEvent('other.json');
+        handler.onEvent('config.json');
+        handler.onEvent(null);
+
+        expect(await reloaded).toBe('Edited Title');
+        expect(reloads).toBe(1);
+        expect(config.get('title')).toBe('Edited Title');
+    });
+
+    it('should ignore changes to other files in the directory', () => {
+        const config = new ConfigManager('config.json');
+        const onChange = mock(() => {});
+        const handler = config.createChangeHandler(onChange, 0);
+
+        handler.onEvent('tokens.json');
+        handler.cancel();
+
+        expect(onChange).not.toHaveBeenCalled();
+    });
+});
+
+describe('toRendererConfig', () => {
+    it('should leave the token and other main-process settings out', () => {
+        const sent = toRendererConfig({ token: 'secret', userAgent: 'UA', title: 'T', game: '1', audienceType: '0' });
+        expect(sent).toEqual({ title: 'T', game: '1', audienceType: '0' });
+        expect(JSON.stringify(sent)).not.toContain('secret');
+    });
+});
+
+describe('validateConfig', () => {
+    it('should report a wrong-type field and fall back to the default', () => {
+        fs.writeFileSync(configFile, JSON.stringify({ audienceType: 0, title: 'Kept' }));
+        const config = new ConfigManager('config.json');
+        const loaded = config.load();
+
+        expect(config.getValidationErrors()).toEqual([{ field: 'audienceType', expected: 'string', actual: 'number' }]);
+        expect(loaded.audienceType).toBe('0');
+        expect(loaded.title).toBe('Kept');
+    });
+
+    it('should accept a valid file', () => {
+        const result = validateConfig({ title: 'T', game: '1', audienceType: '1', watchConfig: 'true', suppressDonationReminder: false });
+        expect(result.errors).toEqual([]);
+        expect(result.config.watchConfig).toBe(true);
+    });
+
+    it('should turn "true"/"false" strings into booleans before they reach the renderer', () => {
+        fs.writeFileSync(configFile, JSON.stringify({ title: 'T', rememberStreamInfo: 'false', suppressDonationReminder: 'true' }));
+        const config = new ConfigManager('config.json');
+        const loaded = config.load();
+
+        expect(loaded.rememberStreamInfo).toBe(false);
+        expect(config.getBool('suppressDonationReminder')).toBe(true);
+        expect(toRendererConfig(loaded)).toMatchObject({ rememberStreamInfo: false, suppressDonationReminder: true });
+    });
+
+    it('should reject a non-object file', () => {
+        expect(validateConfig([]).errors).toEqual([{ field: '$', expected: 'object', actual: 'array' }]);
+    });
+});
@@ -0,0 +1,26 @@
+import { describe, it, expect, afterAll } from 'bun:test';
+import { runConnectivityCheck } from '../src/utils/connectivity';
+
+const server = Bun.serve({
+    port: 0,
+    fetch: () => new Response(null, { status: 404 }),
+});
+const closed = Bun.serve({ port: 0, fetch: () => new Response() });
+const closedUrl = `http://localhost:${closed.port}`;
+closed.stop(true);
+
+afterAll(() => server.stop());
+
+describe('runConnectivityCheck', () => {
+    it('should report reachable and unreachable endpoints', async () => {
+        const report = await runConnectivityCheck({
+            tiktok: `http://localhost:${server.port}/login`,
+            streamlabs: closedUrl,
+        }, 1000);
+
+        expect(report.tiktok.reachable).toBe(true);
+        expect(report.tiktok.latencyMs).toBeGreaterThanOrEqual(0);
+        expect(report.streamlabs.reachable).toBe(false);
+        expect(report.streamlabs.reason).toBeTruthy();
+    });
+});
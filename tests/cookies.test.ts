@@ -0,0 +1,205 @@
+import { describe, it, expect, afterAll } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('cookies');
+
+const { importCookies, clearTikTokCookies, verifyTikTokCookies, parseJsonCookies, loadStoredCookies, hasCookiesFile, validateCookies, getCapturedUserInfo, parseTikTokUserInfo, buildCookieHeader, loadSessionCookies, toCookieSetDetails } = await import('../src/auth/cookies');
+const { ERROR_MESSAGES, AUTH_CONFIG } = await import('../src/constants');
+
+const writeTmp = (name: string, content: string) => {
+    const file = path.join(tmpDir, name);
+    fs.writeFileSync(file, content);
+    return file;
+};
+
+describe('importCookies', () => {
+    it('should import a JSON cookie export', () => {
+        const file = writeTmp('export.json', JSON.stringify([
+            { name: 'sessionid', value: 'abc', domain: '.tiktok.com', path: '/', secure: true, httpOnly: true, expirationDate: 1900000000 },
+        ]));
+
+        const cookies = importCookies(file, 'json');
+
+        expect(cookies).toHaveLength(1);
+        const saved = JSON.parse(fs.readFileSync(path.join(tmpDir, 'cookies.json'), 'utf-8'));
+        expect(saved[0].name).toBe('sessionid');
+        expect(saved[0].expirationDate).toBe(1900000000);
+    });
+
+    it('should import a Netscape cookies.txt file', () => {
+        const file = writeTmp('cookies.txt', [
+            '# Netscape HTTP Cookie File',
+            '#HttpOnly_.tiktok.com\tTRUE\t/\tTRUE\t1900000000\tsessionid\txyz',
+            '.tiktok.com\tTRUE\t/\tFALSE\t0\tttwid\t123',
+        ].join('\n'));
+
+        const cookies = importCookies(file, 'netscape');
+
+        expect(cookies).toHaveLength(2);
+        expect(cookies[0]).toMatchObject({ name: 'sessionid', value: 'xyz', httpOnly: true, secure: true });
+        expect(cookies[1]!.expirationDate).toBeUndefined();
+    });
+
+    it('should reject a file without TikTok session cookies', () => {
+        const file = writeTmp('no-session.json', JSON.stringify([
+            { name: 'ttwid', value: '123', domain: '.tiktok.com', path: '/' },
+        ]));
+
+        expect(() => importCookies(file, 'json')).toThrow(ERROR_MESSAGES.NO_TIKTOK_SESSION);
+    });
+});
+
+describe('loadStoredCookies', () => {
+    it('should treat a malformed cookies file as having no cookies', () => {
+        const file = writeTmp('broken-cookies.json', '[{"name": "sessionid", "value": ');
+
+        expect(loadStoredCookies(file)).toEqual([]);
+        expect(hasCookiesFile(file)).toBe(false);
+    });
+
+    it('should not apply the import size cap to the app\'s own file', () => {
+        const large = [{ name: 'sessionid', value: 'x'.repeat(AUTH_CONFIG.MAX_PAYLOAD_BYTES), domain: '.tiktok.com', path: '/' }];
+        const file = writeTmp('large-cookies.json', JSON.stringify(large));
+
+        expect(loadStoredCookies(file)).toHaveLength(1);
+        expect(loadSessionCookies(file)).toHaveLength(1);
+    });
+});
+
+describe('loadSessionCookies', () => {
+    it('should keep sameSite, hostOnly and expiry for restoring the session', () => {
+        const file = writeTmp('session-cookies.json', JSON.stringify([
+            { name: 'sid', value: 'a', domain: 'www.tiktok.com', path: '/', secure: true, httpOnly: true, hostOnly: true, sameSite: 'lax', expirationDate: 2000000000 },
+            { name: 'tt', value: 'b', domain: '.tiktok.com', path: '/', secure: true, httpOnly: false, hostOnly: false, sameSite: 'no_restriction', session: true },
+        ]));
+
+        const [hostOnly, shared] = loadSessionCookies(file);
+
+        expect(toCookieSetDetails(hostOnly!)).toEqual({
+            url: 'https://www.tiktok.com/', name: 'sid', value: 'a', path: '/', secure: true, httpOnly: true,
+            expirationDate: 2000000000, sameSite: 'lax',
+        });
+        expect(toCookieSetDetails(shared!)).toEqual({
+            url: 'https://tiktok.com/', name: 'tt', value: 'b', path: '/', secure: true, httpOnly: false,
+            domain: '.tiktok.com', sameSite: 'no_restriction',
+        });
+    });
+});
+
+describe('validateCookies', () => {
+    it('should report a missing sessionid', () => {
+        const result = validateCookies([
+            { name: 'sid_tt', value: 'abc', domain: '.tiktok.com', path: '/', secure: true, httpOnly: true },
+        ]);
+
+        expect(result.hasSession).toBe(true);
+        expect(result.missing).toContain('sessionid');
+        expect(result.missing).not.toContain('sid_tt');
+    });
+});
+
+describe('getCapturedUserInfo', () => {
+    it('should extract the user id from multi_sids', () => {
+        const cookie = { domain: '.tiktok.com', path: '/', secure: true, httpOnly: true };
+        expect(getCapturedUserInfo([{ ...cookie, name: 'multi_sids', value: '7012345678901234567%3Aabcdef' }]))
+            .toEqual({ found: true, userId: '7012345678901234567' });
+        expect(getCapturedUserInfo([{ ...cookie, name: 'sessionid', value: 'abc' }])).toEqual({ found: false });
+    });
+});
+
+describe('parseTikTokUserInfo', () => {
+    it('should parse a well-formed user_info blob', () => {
+        const blob = JSON.stringify({ uid: '7012345678901234567', nickname: 'Streamer', secUid: 'MS4w', uniqueId: 'streamer', extra: 1 });
+        expect(parseTikTokUserInfo(blob)).toEqual({ uid: '7012345678901234567', nickname: 'Streamer', secUid: 'MS4w', uniqueId: 'streamer' });
+    });
+
+    it('should keep only the fields that are present', () => {
+        expect(parseTikTokUserInfo({ uid: 42, nickname: '' })).toEqual({ uid: '42' });
+        expect(parseTikTokUserInfo({ nickname: 'no uid' })).toBeNull();
+    });
+
+    it('should return null for a non-JSON value', () => {
+        expect(parseTikTokUserInfo('not json')).toBeNull();
+        expect(parseTikTokUserInfo(null)).toBeNull();
+        expect(parseTikTokUserInfo('"just a string"')).toBeNull();
+    });
+
+    it('should prefer the captured user over cookies', () => {
+        const user = { uid: '1', nickname: 'Streamer' };
+        expect(getCapturedUserInfo([], user)).toEqual({ found: true, userId: '1', user });
+    });
+});
+
+describe('parseJsonCookies', () => {
+    const cookie = { name: 'sessionid', value: 'abc', domain: '.tiktok.com' };
+
+    it('should reject payloads over the size limit', () => {
+        const oversized = JSON.stringify([{ ...cookie, value: 'x'.repeat(200) }]);
+        expect(() => parseJsonCookies(oversized, 100)).toThrow(ERROR_MESSAGES.PAYLOAD_TOO_LARGE(100));
+    });
+
+    it('should accept payloads under the size limit', () => {
+        expect(parseJsonCookies(JSON.stringify([cookie]), 100)).toHaveLength(1);
+    });
+});
+
+describe('clearTikTokCookies', () => {
+    it('should remove cookies.json but keep the token file', () => {
+        writeTmp('cookies.json', JSON.stringify([{ name: 'sessionid', value: 'abc', domain: '.tiktok.com' }]));
+        writeTmp('tokens.json', JSON.stringify({ oauth_token: 'keep' }));
+
+        expect(clearTikTokCookies()).toBe(true);
+        expect(hasCookiesFile()).toBe(false);
+        expect(JSON.parse(fs.readFileSync(path.join(tmpDir, 'tokens.json'), 'utf-8')).oauth_token).toBe('keep');
+    });
+});
+
+describe('verifyTikTokCookies', () => {
+    const tiktok = Bun.serve({
+        port: 0,
+        fetch(req) {
+            if (req.headers.get('cookie')?.includes('sessionid=live')) {
+                return Response.json({ message: 'success', data: { user_id: 123 } });
+            }
+            return new Response(null, { status: 302, headers: { location: '/login?redirect_url=x' } });
+        },
+    });
+    afterAll(() => tiktok.stop());
+
+    const url = `http://localhost:${tiktok.port}/passport/web/account/info/`;
+    const session = (value: string) => [{ name: 'sessionid', value, domain: 'localhost', path: '/', secure: false, httpOnly: true }];
+
+    it('should accept a live session', async () => {
+        expect(await verifyTikTokCookies(url, session('live'))).toEqual({ valid: true });
+    });
+
+    it('should treat a redirect to login as an expired session', async () => {
+        expect(await verifyTikTokCookies(url, session('expired'))).toEqual({ valid: false, reason: 'redirected_to_login' });
+    });
+
+    it('should report missing cookies without a request', async () => {
+        expect(await verifyTikTokCookies(url, [])).toEqual({ valid: false, reason: 'no_cookies' });
+    });
+});
+
+describe('buildCookieHeader', () => {
+    const cookie = (name: string, fields: { path?: string; secure?: boolean } = {}) => ({
+        name, value: 'v', domain: '.streamlabs.com', path: fields.path ?? '/', secure: fields.secure ?? false, httpOnly: true,
+    });
+
+    it('should only send cookies whose path covers the request path', () => {
+        const cookies = [cookie('root'), cookie('api', { path: '/api' }), cookie('other', { path: '/dashboard' })];
+
+        expect(buildCookieHeader(cookies, 'https://streamlabs.com/api/auth/data')).toBe('root=v; api=v');
+        expect(buildCookieHeader(cookies, 'https://streamlabs.com/apix')).toBe('root=v');
+    });
+
+    it('should send secure cookies over https only', () => {
+        const cookies = [cookie('plain'), cookie('locked', { secure: true })];
+
+        expect(buildCookieHeader(cookies, 'https://streamlabs.com/')).toBe('plain=v; locked=v');
+        expect(buildCookieHeader(cookies, 'http://streamlabs.com/')).toBe('plain=v');
+    });
+});
@@ -0,0 +1,229 @@
+import { describe, it, expect, mock } from 'bun:test';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('login');
+
+const { StreamlabsAuth, LoginSession, validateCustomScript, buildLoginCheckScript, parseLoginIndicators } = await import('../src/auth/electron-login');
+const { IPC_CHANNELS, ERROR_MESSAGES, AUTH_CONFIG } = await import('../src/constants');
+const { setSpanSink } = await import('../src/utils/trace');
+
+function createAuth(fetchResult: unknown) {
+    const events: Array<[string, unknown]> = [];
+    const auth = new StreamlabsAuth('https://auth', path.join(tmpDir, 'cookies.json'), 'verifier',
+        (channel, payload) => events.push([channel, payload]));
+    const executeJavaScript = mock(() => Promise.resolve(fetchResult));
+    (auth as any).window = {
+        webContents: { executeJavaScript, getURL: () => 'https://streamlabs.com/' },
+        close: () => {},
+        isDestroyed: () => false,
+    };
+    const token = new Promise((resolve, reject) => {
+        (auth as any).resolveToken = resolve;
+        (auth as any).rejectToken = reject;
+    });
+    return { auth, events, token, executeJavaScript };
+}
+
+describe('StreamlabsAuth', () => {
+    it('should emit token exchange progress events in order', async () => {
+        const { auth, events, token } = createAuth({ success: true, data: { success: true, data: { oauth_token: 'abc' } } });
+
+        await (auth as any).executeTokenFetch('code123');
+
+        expect(await token).toEqual({ oauth_token: 'abc' });
+        expect(events).toEqual([
+            [IPC_CHANNELS.TOKEN_EXCHANGE_PROGRESS, { status: 'request_sent' }],
+            [IPC_CHANNELS.TOKEN_EXCHANGE_PROGRESS, { status: 'response_received' }],
+            [IPC_CHANNELS.TOKEN_EXCHANGE_PROGRESS, { status: 'token_extracted' }],
+            [IPC_CHANNELS.TOKEN_EXCHANGE_COMPLETE, { success: true }],
+        ]);
+    });
+
+    it('should only run one token exchange when success is reported twice', async () => {
+        const { auth, token, executeJavaScript } = createAuth({ success: true, data: { success: true, data: { oauth_token: 'abc' } } });
+        const successUrl = 'https://streamlabs.com/dashboard?code=code123&success=true';
+
+        (auth as any).checkSuccess(successUrl);
+        (auth as any).checkSuccess(successUrl);
+        await token;
+
+        expect(executeJavaScript).toHaveBeenCalledTimes(1);
+    });
+
+    it('should record a token_exchange span with non-sensitive fields', async () => {
+        const spans: any[] = [];
+        const restore = setSpanSink(span => spans.push(span));
+        try {
+            const { auth, token } = createAuth({ success: true, status: 200, data: { success: true, data: { oauth_token: 'abc' } } });
+            await (auth as any).executeTokenFetch('code123');
+            await token;
+        } finally {
+            restore();
+        }
+
+        const exchange = spans.find(span => span.name === 'token_exchange');
+        expect(exchange.fields).toEqual({ status: 'token_extracted', attempts: 1, http_status: 200, outcome: 'success' });
+        expect(JSON.stringify(exchange)).not.toContain('abc');
+    });
+});
+
+describe('StreamlabsAuth window close', () => {
+    function openWindow(auth: any) {
+        const handlers: Record<string, () => void> = {};
+        auth.window = {
+            webContents: { on: () => {}, executeJavaScript: mock(() => Promise.resolve({})) },
+            on: (event: string, handler: () => void) => { handlers[event] = handler; },
+            close: () => {},
+            isDestroyed: () => false,
+        };
+        auth.setupLifecycle();
+        return handlers;
+    }
+
+    it('should clear the window and reject when closed mid-login', async () => {
+        const { auth, token } = createAuth({});
+        const handlers = openWindow(auth);
+
+        handlers.closed!();
+
+        expect(auth.isWindowOpen()).toBe(false);
+        await expect(token).rejects.toThrow(ERROR_MESSAGES.WINDOW_CLOSED);
+    });
+
+    it('should fail the token exchange instead of hanging when closed during it', async () => {
+        const { auth, token, events } = createAuth({});
+        const handlers = openWindow(auth);
+        (auth as any).tokenFetchStarted = true;
+
+        handlers.closed!();
+        await (auth as any).executeTokenFetch('code123');
+
+        await expect(token).rejects.toThrow(ERROR_MESSAGES.FETCH_FAILED);
+        expect(events.at(-1)).toEqual([IPC_CHANNELS.TOKEN_EXCHANGE_FAILED, { success: false, error: ERROR_MESSAGES.WINDOW_CLOSED }]);
+    });
+
+    it('should report window_closed when the window closes while the fetch script runs', async () => {
+        const { auth, token } = createAuth({});
+        const handlers = openWindow(auth);
+        (auth as any).tokenFetchStarted = true;
+        (auth as any).window.webContents.executeJavaScript = mock(async () => {
+            handlers.closed!();
+            throw new Error('Render frame was disposed before WebFrameMain could be accessed');
+        });
+
+        const settled = token.catch(e => e);
+        await (auth as any).executeTokenFetch('code123');
+
+        expect((await settled).kind).toBe('window_closed');
+    });
+});
+
+describe('StreamlabsAuth.cancel', () => {
+    it('should stop token exchange polling promptly', async () => {
+        const { auth, token, executeJavaScript } = createAuth({ success: true, data: { success: false } });
+
+        const settled = token.catch(e => e);
+        const exchange = (auth as any).executeTokenFetch('code123');
+        await new Promise(resolve => setTimeout(resolve, 10));
+        const cancelledAt = Date.now();
+        auth.cancel();
+        await exchange;
+
+        expect(Date.now() - cancelledAt).toBeLessThan(100);
+        expect(executeJavaScript).toHaveBeenCalledTimes(1);
+        expect(auth.isWindowOpen()).toBe(false);
+        expect((await settled).kind).toBe('cancelled');
+    });
+});
+
+describe('LoginSession', () => {
+    it('should close the window once however often it is disposed', () => {
+        const close = mock(() => {});
+        const loginSession = new LoginSession({ close, isDestroyed: () => false });
+        loginSession.listen(IPC_CHANNELS.LOG_CONSOLE, () => {});
+
+        loginSession.dispose();
+        loginSession.dispose();
+
+        expect(close).toHaveBeenCalledTimes(1);
+        expect(loginSession.isDisposed()).toBe(true);
+    });
+
+    it('should not close a window the user already closed', () => {
+        const close = mock(() => {});
+        new LoginSession({ close, isDestroyed: () => true }).dispose();
+        expect(close).not.toHaveBeenCalled();
+    });
+});
+
+describe('StreamlabsAuth navigation', () => {
+    it('should process identical consecutive navigations once', () => {
+        const { auth } = createAuth({});
+        const onTikTokLoggedIn = mock(() => {});
+        (auth as any).onTikTokLoggedIn = onTikTokLoggedIn;
+
+        (auth as any).handleNavigation('https://www.tiktok.com/foryou', true);
+        (auth as any).handleNavigation('https://www.tiktok.com/foryou', true);
+
+        expect(onTikTokLoggedIn).toHaveBeenCalledTimes(1);
+    });
+
+    it('should schedule exactly one re-injection for in-page navigations', async () => {
+        const { auth } = createAuth({});
+        const inject = mock(() => {});
+        (auth as any).injectManualAuthButton = inject;
+        (auth as any).captureUserInfo = () => {};
+
+        (auth as any).handleNavigation('https://www.tiktok.com/login/phone', false);
+        (auth as any).handleNavigation('https://www.tiktok.com/login/email', false);
+        await new Promise(resolve => setTimeout(resolve, AUTH_CONFIG.REINJECT_DELAY_MS + 50));
+
+        expect(inject).toHaveBeenCalledTimes(1);
+    });
+});
+
+describe('validateCustomScript', () => {
+    it('should reject scripts over the size limit', () => {
+        expect(() => validateCustomScript('x'.repeat(11), 10)).toThrow(ERROR_MESSAGES.SCRIPT_TOO_LARGE(10));
+        expect(() => validateCustomScript('x'.repeat(10), 10)).not.toThrow();
+    });
+
+    it('should reject navigation hijacks', () => {
+        expect(() => validateCustomScript("window.location.href = 'https://evil'")).toThrow(ERROR_MESSAGES.SCRIPT_UNSAFE);
+        expect(() => validateCustomScript("console.log(location.href === 'x')")).not.toThrow();
+    });
+});
+
+describe('buildLoginCheckScript', () => {
+    it('should interpolate a custom indicator set', () => {
+        const script = buildLoginCheckScript({ selectors: ['#new-avatar'], cookieNames: ['new_session'] });
+        expect(script).toContain('["#new-avatar"]');
+        expect(script).toContain('["new_session"]');
+        expect(script).not.toContain('user-avatar');
+    });
+
+    it('should check same-origin iframes and skip cross-origin ones', () => {
+        const script = buildLoginCheckScript();
+        expect(script).toContain('window.frames.length');
+        expect(script).toMatch(/try \{\s*const frameDocument = window\.frames\[i\]\.document;[\s\S]*\} catch \(e\)/);
+    });
+});
+
+describe('parseLoginIndicators', () => {
+    it('should surface the indicators that fired', () => {
+        expect(parseLoginIndicators({ selectors: ['[data-e2e="user-avatar"]'], cookies: ['sid_tt'] }))
+            .toEqual(['user_element:[data-e2e="user-avatar"]', 'cookie:sid_tt']);
+    });
+
+    it('should treat an empty or malformed result as not logged in', () => {
+        expect(parseLoginIndicators({ selectors: [], cookies: [] })).toEqual([]);
+        expect(parseLoginIndicators(true)).toEqual([]);
+    });
+
+    it('should record url_pattern when the URL signals a login', () => {
+        const { auth } = createAuth({});
+        (auth as any).handleNavigation('https://www.tiktok.com/foryou?i=1', true);
+        expect(auth.getLoginIndicators()).toEqual(['url_pattern']);
+    });
+});
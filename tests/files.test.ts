@@ -0,0 +1,147 @@
+import { describe, it, expect, spyOn } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('files');
+
+const { listCredentialFiles, deleteCredentialFile, setPersistSecrets, setCredentialDir, getCredentialDir, TokenStorage, FileUtils, CredentialError, withFsRetry } = await import('../src/utils/fileUtils');
+
+describe('credential files', () => {
+    it('should list existing files and delete one', () => {
+        fs.writeFileSync(path.join(tmpDir, 'tokens.json'), '{"oauth_token":"x"}');
+        fs.writeFileSync(path.join(tmpDir, 'cookies.json'), '[]');
+
+        const listed = listCredentialFiles();
+        expect(listed.find(f => f.name === 'tokens.json')).toMatchObject({ exists: true, size: 19 });
+        expect(listed.find(f => f.name === 'config.json')).toEqual({ name: 'config.json', exists: false });
+
+        expect(deleteCredentialFile('tokens.json')).toBe(true);
+        expect(fs.existsSync(path.join(tmpDir, 'tokens.json'))).toBe(false);
+        expect(fs.existsSync(path.join(tmpDir, 'cookies.json'))).toBe(true);
+    });
+
+    it('should refuse names outside the allowlist', () => {
+        expect(() => deleteCredentialFile('../package.json')).toThrow();
+    });
+});
+
+describe('setPersistSecrets', () => {
+    it('should keep secrets in memory only when persistence is off', () => {
+        fs.rmSync(path.join(tmpDir, 'tokens.json'), { force: true });
+        setPersistSecrets(false);
+        try {
+            const storage = new TokenStorage('tokens.json');
+            storage.save({ oauth_token: 'in-memory' });
+
+            expect(fs.existsSync(path.join(tmpDir, 'tokens.json'))).toBe(false);
+            expect(storage.get()).toBe('in-memory');
+            expect(listCredentialFiles().find(f => f.name === 'tokens.json')).toMatchObject({ exists: true, inMemory: true });
+
+            expect(deleteCredentialFile('tokens.json')).toBe(true);
+            expect(listCredentialFiles().find(f => f.name === 'tokens.json')).toEqual({ name: 'tokens.json', exists: false });
+        } finally {
+            setPersistSecrets(true);
+        }
+    });
+});
+
+describe('setCredentialDir', () => {
+    it('should redirect where credential files are written and read', () => {
+        const profileDir = path.join(tmpDir, 'profile-a');
+        fs.mkdirSync(profileDir, { recursive: true });
+        setCredentialDir(profileDir);
+        try {
+            new TokenStorage('tokens.json').save({ oauth_token: 'profile-token' });
+            FileUtils.writeJson('cookies.json', []);
+
+            expect(fs.existsSync(path.join(profileDir, 'tokens.json'))).toBe(true);
+            expect(fs.existsSync(path.join(profileDir, 'cookies.json'))).toBe(true);
+            expect(new TokenStorage('tokens.json').get()).toBe('profile-token');
+            expect(listCredentialFiles().find(f => f.name === 'tokens.json')?.exists).toBe(true);
+        } finally {
+            setCredentialDir(null);
+        }
+        expect(new TokenStorage('tokens.json').get()).not.toBe('profile-token');
+    });
+
+    it('should reject relative, missing and non-directory paths and keep the current directory', () => {
+        const file = path.join(tmpDir, 'not-a-dir.txt');
+        fs.writeFileSync(file, '');
+
+        for (const dir of ['relative/profile', path.join(tmpDir, 'missing-profile'), file]) {
+            const error = (() => { try { setCredentialDir(dir); } catch (e) { return e; } })() as any;
+            expect(error).toBeInstanceOf(CredentialError);
+            expect(error.kind).toBe('invalid_dir');
+        }
+        expect(fs.existsSync(path.join(tmpDir, 'missing-profile'))).toBe(false);
+        expect(getCredentialDir()).toBe(tmpDir);
+    });
+});
+
+describe('TokenStorage checksum', () => {
+    const tokensPath = path.join(tmpDir, 'tokens.json');
+
+    it('should write a checksum and load the token back', () => {
+        new TokenStorage('tokens.json').save({ oauth_token: 'checked' });
+
+        expect(JSON.parse(fs.readFileSync(tokensPath, 'utf-8')).checksum).toMatch(/^[0-9a-f]{64}$/);
+        expect(new TokenStorage('tokens.json').load()).toEqual({ oauth_token: 'checked' });
+    });
+
+    it('should reject a file that no longer matches its checksum', () => {
+        new TokenStorage('tokens.json').save({ oauth_token: 'checked' });
+        const tampered = JSON.parse(fs.readFileSync(tokensPath, 'utf-8'));
+        tampered.oauth_token = 'edited';
+        fs.writeFileSync(tokensPath, JSON.stringify(tampered));
+        const errorSpy = spyOn(console, 'error').mockImplementation(() => {});
+
+        try {
+            const storage = new TokenStorage('tokens.json');
+            expect(() => storage.load()).toThrow(CredentialError);
+            expect(storage.get()).toBeNull();
+        } finally {
+            errorSpy.mockRestore();
+        }
+    });
+
+    it('should accept a legacy file without a checksum', () => {
+        fs.writeFileSync(tokensPath, JSON.stringify({ oauth_token: 'legacy' }));
+        const warnSpy = spyOn(console, 'warn').mockImplementation(() => {});
+
+        try {
+            expect(new TokenStorage('tokens.json').get()).toBe('legacy');
+            expect(warnSpy).toHaveBeenCalled();
+        } finally {
+            warnSpy.mockRestore();
+        }
+    });
+});
+
+describe('withFsRetry', () => {
+    const lockError = () => Object.assign(new Error('EPERM: operation not permitted'), { code: 'EPERM' });
+
+    it('should recover from a transient lock error', () => {
+        const target = path.join(tmpDir, 'locked.json');
+        let calls = 0;
+        withFsRetry(() => {
+            calls += 1;
+            if (calls < 3) throw lockError();
+            fs.writeFileSync(target, '{}');
+        }, 3);
+
+        expect(calls).toBe(3);
+        expect(fs.readFileSync(target, 'utf-8')).toBe('{}');
+    });
+
+    it('should give up after the last attempt and not retry other errors', () => {
+        let calls = 0;
+        expect(() => withFsRetry(() => { calls += 1; throw lockError(); }, 2)).toThrow('EPERM');
+        expect(calls).toBe(2);
+
+        calls = 0;
+        expect(() => withFsRetry(() => { calls += 1; throw Object.assign(new Error('ENOSPC'), { code: 'ENOSPC' }); }, 3)).toThrow('ENOSPC');
+        expect(calls).toBe(1);
+    });
+});
+
@@ -0,0 +1,21 @@
+import { mock } from 'bun:test';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+
+/**
+ * Mock the `electron` module with the app path pointing at a fresh temp
+ * directory, so each test file reads and writes its own config/credential files.
+ * Call it before importing anything under src/ (use top-level `await import`).
+ * Returns the temp directory.
+ */
+export function mockElectron(name: string, app: Record<string, unknown> = {}): string {
+    const tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), `keygen-${name}-`));
+    mock.module('electron', () => ({
+        app: { isPackaged: false, getAppPath: () => tmpDir, ...app },
+        BrowserWindow: class {},
+        ipcMain: { on: () => {}, removeListener: () => {} },
+        session: { defaultSession: { cookies: { get: async () => [], set: async () => {} } } },
+    }));
+    return tmpDir;
+}
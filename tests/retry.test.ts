@@ -0,0 +1,61 @@
+import { describe, it, expect } from 'bun:test';
+import { retry, type RetryPolicy } from '../src/utils/retry';
+
+const policy = (maxAttempts: number): RetryPolicy => ({ maxAttempts, initialDelayMs: 1, maxDelayMs: 2 });
+
+function failingTimes(failures: number) {
+    let calls = 0;
+    const op = async () => {
+        calls += 1;
+        if (calls <= failures) throw new Error(`failure ${calls}`);
+        return 'ok';
+    };
+    return { op, calls: () => calls };
+}
+
+describe('retry', () => {
+    it('should succeed after K transient failures', async () => {
+        const { op, calls } = failingTimes(2);
+        const outcome = await retry(policy(5), op, { retryOnError: () => true });
+
+        expect(outcome).toEqual({ result: 'ok', attempts: 3 });
+        expect(calls()).toBe(3);
+    });
+
+    it('should rethrow the last error once attempts are used up', async () => {
+        const { op, calls } = failingTimes(5);
+        await expect(retry(policy(3), op, { retryOnError: () => true })).rejects.toThrow('failure 3');
+        expect(calls()).toBe(3);
+    });
+
+    it('should not retry errors by default', async () => {
+        const { op, calls } = failingTimes(1);
+        await expect(retry(policy(5), op)).rejects.toThrow('failure 1');
+        expect(calls()).toBe(1);
+    });
+
+    it('should keep polling while the result is pending', async () => {
+        const outcome = await retry(policy(5), async attempt => attempt, { retryOnResult: n => n < 4 });
+        expect(outcome).toEqual({ result: 4, attempts: 4 });
+    });
+
+    it('should return the last pending result when attempts run out', async () => {
+        const outcome = await retry(policy(2), async () => 'pending', { retryOnResult: () => true });
+        expect(outcome).toEqual({ result: 'pending', attempts: 2 });
+    });
+
+    it('should stop waiting between attempts once aborted', async () => {
+        const controller = new AbortController();
+        let calls = 0;
+        const pending = retry({ maxAttempts: 5, initialDelayMs: 10_000, maxDelayMs: 10_000 }, async () => {
+            calls += 1;
+            return 'pending';
+        }, { retryOnResult: () => true, signal: controller.signal });
+
+        controller.abort(new Error('cancelled'));
+
+        await expect(pending).rejects.toThrow('cancelled');
+        expect(calls).toBe(1);
+    });
+});
+
@@ -0,0 +1,57 @@
+import { describe, it, expect } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('bundle', { getVersion: () => '1.0.0' });
+const readJson = (name: string) => JSON.parse(fs.readFileSync(path.join(tmpDir, name), 'utf-8'));
+
+const { exportSessionBundle, importSessionBundle } = await import('../src/auth/sessionBundle');
+const { ERROR_MESSAGES } = await import('../src/constants');
+
+fs.writeFileSync(path.join(tmpDir, 'cookies.json'), JSON.stringify([
+    { name: 'sessionid', value: 'session-secret', domain: '.tiktok.com', path: '/', secure: true, httpOnly: true },
+]));
+fs.writeFileSync(path.join(tmpDir, 'tokens.json'), JSON.stringify({ oauth_token: 'token-secret', expires_at: '2030-01-01T00:00:00.000Z' }));
+
+describe('exportSessionBundle', () => {
+    it('should mask values in a redacted bundle', () => {
+        const bundle = exportSessionBundle({ redact: true });
+
+        expect(bundle.redacted).toBe(true);
+        expect(bundle.cookies[0]!.value).toBe('sess***');
+        expect(bundle.token).toEqual({ present: true, expires_at: '2030-01-01T00:00:00.000Z' });
+        expect(JSON.stringify(bundle)).not.toContain('secret');
+    });
+
+    it('should include real values in an unredacted bundle', () => {
+        const bundle = exportSessionBundle({ redact: false });
+
+        expect(bundle.appVersion).toBe('1.0.0');
+        expect(bundle.cookies[0]!.value).toBe('session-secret');
+        expect(bundle.token.data?.oauth_token).toBe('token-secret');
+    });
+});
+
+describe('importSessionBundle', () => {
+    it('should restore cookies and token from a valid bundle', () => {
+        const bundle = exportSessionBundle({ redact: false });
+        fs.rmSync(path.join(tmpDir, 'cookies.json'));
+        fs.rmSync(path.join(tmpDir, 'tokens.json'));
+
+        expect(importSessionBundle(bundle)).toEqual({ cookieCount: 1, tokenImported: true });
+        expect(readJson('cookies.json')[0].value).toBe('session-secret');
+        expect(readJson('tokens.json').oauth_token).toBe('token-secret');
+    });
+
+    it('should reject a redacted bundle', () => {
+        const bundle = exportSessionBundle({ redact: true });
+        expect(() => importSessionBundle(bundle)).toThrow(ERROR_MESSAGES.REDACTED_SESSION_BUNDLE);
+        expect(() => importSessionBundle({ ...bundle, redacted: false })).toThrow(ERROR_MESSAGES.REDACTED_SESSION_BUNDLE);
+    });
+
+    it('should reject an unknown shape', () => {
+        expect(() => importSessionBundle({ version: 99, cookies: [] })).toThrow(ERROR_MESSAGES.INVALID_SESSION_BUNDLE);
+        expect(() => importSessionBundle(null)).toThrow(ERROR_MESSAGES.INVALID_SESSION_BUNDLE);
+    });
+});
@@ -0,0 +1,31 @@
+import { describe, it, expect } from 'bun:test';
+import { codePointLength, safeSlice, sanitizeText } from '../src/utils/strUtils';
+import { truncate } from '../src/utils/apiClient';
+
+describe('safeSlice', () => {
+    it('should handle empty strings and out-of-range indices', () => {
+        expect(safeSlice('', 0, 5)).toBe('');
+        expect(safeSlice('abc', 0, 25)).toBe('abc');
+        expect(safeSlice('abc', 5)).toBe('');
+        expect(safeSlice('abcdef', -4)).toBe('cdef');
+    });
+
+    it('should never split a multibyte character', () => {
+        const text = 'ab😀ñ中';
+        expect(codePointLength(text)).toBe(5);
+        expect(safeSlice(text, 0, 3)).toBe('ab😀');
+        expect(safeSlice(text, -2)).toBe('ñ中');
+        expect(truncate('😀'.repeat(30), 25)).toBe('😀'.repeat(25));
+    });
+});
+
+describe('sanitizeText', () => {
+    it('should clean embedded newlines and control characters', () => {
+        expect(sanitizeText('  Late night\r\n\tstream\u0000  chill \n')).toBe('Late night stream chill');
+    });
+
+    it('should leave a normal title unchanged', () => {
+        const title = 'Ranked grind 🎮 with 👨‍👩‍👧 — ñandú 中文';
+        expect(sanitizeText(title)).toBe(title);
+    });
+});
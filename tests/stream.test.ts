@@ -0,0 +1,283 @@
+import { describe, it, expect, mock } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('stream');
+
+const { ConfigManager } = await import('../src/config/ConfigManager');
+const { StreamManager, StreamError, validateStreamParams, validateHistoryLimit, formatIngestForObs } = await import('../src/stream/StreamManager');
+const { ApiError } = await import('../src/utils/apiClient');
+const { ERROR_MESSAGES } = await import('../src/constants');
+
+describe('StreamManager', () => {
+    const fakeApi: any = {
+        start: mock(() => Promise.resolve({ rtmpUrl: 'rtmp://test', streamKey: 'key123', id: '123' })),
+    };
+
+    it('should persist the title and category after starting a stream', async () => {
+        fs.writeFileSync(path.join(tmpDir, 'config.json'), JSON.stringify({ token: 'keep-me' }));
+        const config = new ConfigManager('config.json');
+        config.load();
+        const manager = new StreamManager(() => fakeApi, config, () => 'fake-token');
+
+        await manager.start('My Stream', '100');
+
+        const saved = JSON.parse(fs.readFileSync(path.join(tmpDir, 'config.json'), 'utf-8'));
+        expect(saved.title).toBe('My Stream');
+        expect(saved.game).toBe('100');
+        expect(saved.token).toBe('keep-me');
+        expect(manager.getLastStreamInfo()).toEqual({ title: 'My Stream', category: '100' });
+    });
+
+    it('should leave title and game untouched when rememberStreamInfo is off', async () => {
+        fs.writeFileSync(path.join(tmpDir, 'config.json'), JSON.stringify({ title: 'Old', game: '1' }));
+        const config = new ConfigManager('config.json');
+        config.load();
+        const manager = new StreamManager(() => fakeApi, config, () => 'fake-token');
+
+        manager.setRememberStreamInfo(false);
+        await manager.start('New Stream', '200');
+
+        const saved = JSON.parse(fs.readFileSync(path.join(tmpDir, 'config.json'), 'utf-8'));
+        expect(saved).toEqual({ audienceType: '0', suppressDonationReminder: false, title: 'Old', game: '1', rememberStreamInfo: false });
+        expect(manager.shouldRememberStreamInfo()).toBe(false);
+    });
+
+    it('should reject stream commands when no token is available', async () => {
+        fs.rmSync(path.join(tmpDir, 'tokens.json'), { force: true });
+        const manager = new StreamManager(() => fakeApi, new ConfigManager('config.json'));
+
+        await expect(manager.start('My Stream', '100')).rejects.toThrow(ERROR_MESSAGES.MISSING_TOKEN);
+    });
+
+    it('should allow stream commands when tokens.json holds a token', async () => {
+        fs.writeFileSync(path.join(tmpDir, 'tokens.json'), JSON.stringify({ oauth_token: 'saved-token' }));
+        const manager = new StreamManager(() => fakeApi, new ConfigManager('config.json'));
+
+        const info = await manager.start('My Stream', '100');
+        expect(info?.id).toBe('123');
+    });
+
+    it('should map an unauthorized API error to StreamError', async () => {
+        const rejectingApi: any = {
+            start: () => Promise.reject(new ApiError('unauthorized', 'Unauthorized', 401)),
+        };
+        const manager = new StreamManager(() => rejectingApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = await manager.start('My Stream', '100').catch(e => e);
+        expect(error).toBeInstanceOf(StreamError);
+        expect(error.kind).toBe('unauthorized');
+    });
+
+    it('should report no active stream when ending without a stream id', async () => {
+        const idleApi: any = { getCurrentStreamId: () => null, end: mock(() => Promise.resolve(true)) };
+        const manager = new StreamManager(() => idleApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = await manager.end().catch(e => e);
+        expect(error).toBeInstanceOf(StreamError);
+        expect(error.kind).toBe('no_active_stream');
+        expect(idleApi.end).not.toHaveBeenCalled();
+    });
+
+    it('should end the current stream before starting a new one on restart', async () => {
+        const calls: string[] = [];
+        const liveApi: any = {
+            getCurrentStreamId: () => 'old-stream',
+            end: async () => { calls.push('end'); return true; },
+            start: async () => { calls.push('start'); return { rtmpUrl: 'rtmp://new', streamKey: 'newkey', id: 'new-stream' }; },
+        };
+        const manager = new StreamManager(() => liveApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const info = await manager.restart('My Stream', '100');
+
+        expect(calls).toEqual(['end', 'start']);
+        expect(info?.id).toBe('new-stream');
+    });
+
+    it('should not start a second stream when ending the current one fails', async () => {
+        const start = mock(() => Promise.resolve({ rtmpUrl: 'rtmp://new', streamKey: 'newkey', id: 'new-stream' }));
+        const liveApi: any = {
+            getCurrentStreamId: () => 'old-stream',
+            end: () => Promise.reject(new ApiError('http', 'Internal Server Error', 500)),
+            start,
+        };
+        const manager = new StreamManager(() => liveApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = await manager.restart('My Stream', '100').catch(e => e);
+
+        expect(error).toBeInstanceOf(ApiError);
+        expect(error.status).toBe(500);
+        expect(start).not.toHaveBeenCalled();
+    });
+
+    it('should not start a second stream when the API declines to end the current one', async () => {
+        const start = mock(() => Promise.resolve({ rtmpUrl: 'rtmp://new', streamKey: 'newkey', id: 'new-stream' }));
+        const liveApi: any = {
+            getCurrentStreamId: () => 'old-stream',
+            // StreamAPI.end() resolves false for a {success:false} response
+            end: () => Promise.resolve(false),
+            start,
+        };
+        const manager = new StreamManager(() => liveApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = await manager.restart('My Stream', '100').catch(e => e);
+
+        expect(error).toBeInstanceOf(StreamError);
+        expect(error.kind).toBe('end_failed');
+        expect(start).not.toHaveBeenCalled();
+    });
+
+    it('should start a new stream when the old one is already gone (404)', async () => {
+        const liveApi: any = {
+            getCurrentStreamId: () => 'old-stream',
+            end: () => Promise.reject(new ApiError('http', 'Not Found', 404)),
+            start: async () => ({ rtmpUrl: 'rtmp://new', streamKey: 'newkey', id: 'new-stream' }),
+        };
+        const manager = new StreamManager(() => liveApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        expect((await manager.restart('My Stream', '100'))?.id).toBe('new-stream');
+    });
+});
+
+describe('StreamManager.start guard', () => {
+    it('should create only one stream for concurrent starts', async () => {
+        let created = 0;
+        const slowApi: any = {
+            start: async () => {
+                created += 1;
+                await new Promise(resolve => setTimeout(resolve, 5));
+                return { rtmpUrl: 'rtmp://test', streamKey: 'key', id: `stream-${created}` };
+            },
+            getCurrentStream: async () => ({ id: `stream-${created}` }),
+        };
+        const manager = new StreamManager(() => slowApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const [first, second] = await Promise.all([manager.start('A', '1'), manager.start('A', '1')]);
+        const third = await manager.start('A', '1');
+
+        expect(created).toBe(1);
+        expect(second).toBe(first);
+        expect(third).toBe(first);
+    });
+
+    it('should start a new stream once the cached one ended outside the app', async () => {
+        let created = 0;
+        const api: any = {
+            start: async () => ({ rtmpUrl: 'rtmp://test', streamKey: 'key', id: `stream-${++created}` }),
+            getCurrentStream: async () => null,
+        };
+        const manager = new StreamManager(() => api, new ConfigManager('config.json'), () => 'fake-token');
+
+        const first = await manager.start('A', '1');
+        const second = await manager.start('B', '2');
+
+        expect(first?.id).toBe('stream-1');
+        expect(second?.id).toBe('stream-2');
+    });
+
+    it('should keep the cached stream when the liveness check fails', async () => {
+        const start = mock(async () => ({ rtmpUrl: 'rtmp://test', streamKey: 'key', id: 'live' }));
+        let lookupFails = false;
+        const api: any = {
+            start,
+            getCurrentStream: async () => {
+                if (lookupFails) throw new ApiError('network', 'Network Error');
+                return { id: 'live' };
+            },
+        };
+        const manager = new StreamManager(() => api, new ConfigManager('config.json'), () => 'fake-token');
+        await manager.start('A', '1');
+
+        lookupFails = true;
+        const error = await manager.start('A', '1').catch(e => e);
+        expect(error).toBeInstanceOf(ApiError);
+        expect(start).toHaveBeenCalledTimes(1);
+
+        lookupFails = false;
+        expect((await manager.start('A', '1'))?.id).toBe('live');
+        expect(start).toHaveBeenCalledTimes(1);
+    });
+
+    it('should reject a different title or category while the stream is live', async () => {
+        const api: any = {
+            start: async () => ({ rtmpUrl: 'rtmp://test', streamKey: 'key', id: 'live' }),
+            getCurrentStream: async () => ({ id: 'live' }),
+        };
+        const manager = new StreamManager(() => api, new ConfigManager('config.json'), () => 'fake-token');
+        await manager.start('A', '1');
+
+        const error = await manager.start('B', '1').catch(e => e);
+        expect(error).toBeInstanceOf(StreamError);
+        expect(error.kind).toBe('already_live');
+    });
+});
+
+describe('StreamManager title sanitizing', () => {
+    it('should send and remember the cleaned title', async () => {
+        const start = mock((_title: string, _category: string) => Promise.resolve({ rtmpUrl: 'rtmp://test', streamKey: 'key', id: '1' }));
+        const manager = new StreamManager(() => ({ start }) as any, new ConfigManager('config.json'), () => 'fake-token');
+        manager.setRememberStreamInfo(true);
+
+        await manager.start('My\nStream\t ', '100');
+
+        expect(start.mock.calls[0]?.[0]).toBe('My Stream');
+        expect(manager.getLastStreamInfo().title).toBe('My Stream');
+    });
+});
+
+describe('validateStreamParams', () => {
+    it('should count emoji titles by character rather than UTF-16 length', () => {
+        const title = '🎮'.repeat(100);
+        expect(title.length).toBe(200);
+        expect(() => validateStreamParams(title)).not.toThrow();
+        expect(() => validateStreamParams(title + '🎮')).toThrow(StreamError);
+    });
+});
+
+describe('validateHistoryLimit', () => {
+    it('should accept positive whole numbers', () => {
+        expect(() => validateHistoryLimit(1)).not.toThrow();
+        expect(() => validateHistoryLimit(50)).not.toThrow();
+    });
+
+    it('should reject NaN, negative, zero and fractional limits', () => {
+        for (const limit of [NaN, -3, 0, 2.5, Infinity, '10']) {
+            expect(() => validateHistoryLimit(limit)).toThrow(ERROR_MESSAGES.INVALID_HISTORY_LIMIT);
+        }
+    });
+
+    it('should not call the API for an invalid limit', async () => {
+        const getStreamHistory = mock(async () => []);
+        const manager = new StreamManager(() => ({ getStreamHistory }) as any, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = await manager.getStreamHistory(-1).catch(e => e);
+
+        expect(error.kind).toBe('invalid_parameters');
+        expect(getStreamHistory).not.toHaveBeenCalled();
+    });
+});
+
+describe('StreamManager.getIngest', () => {
+    it('should return the RTMP target of the live stream', async () => {
+        const liveApi: any = {
+            start: () => Promise.resolve({ rtmpUrl: 'rtmps://ingest', streamKey: 'live-key', id: '1' }),
+            getCurrentStreamId: () => '1',
+        };
+        const manager = new StreamManager(() => liveApi, new ConfigManager('config.json'), () => 'fake-token');
+        await manager.start('Live', '100');
+
+        const ingest = manager.getIngest();
+        expect(ingest).toEqual({ server: 'rtmps://ingest', key: 'live-key' });
+        expect(formatIngestForObs(ingest)).toBe('Server: rtmps://ingest\nStream Key: live-key');
+    });
+
+    it('should raise no_active_stream when not live', () => {
+        const idleApi: any = { getCurrentStreamId: () => null };
+        const manager = new StreamManager(() => idleApi, new ConfigManager('config.json'), () => 'fake-token');
+
+        const error = (() => { try { manager.getIngest(); } catch (e) { return e; } })() as any;
+        expect(error).toBeInstanceOf(StreamError);
+        expect(error.kind).toBe('no_active_stream');
+    });
+});
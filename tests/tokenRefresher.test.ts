@@ -0,0 +1,95 @@
+import { describe, it, expect, afterAll, spyOn } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+const tmpDir = mockElectron('refresh');
+
+const { TokenRefresher } = await import('../src/auth/tokenRefresher');
+
+let refreshBody = '';
+let refreshCalls = 0;
+const server = Bun.serve({
+    port: 0,
+    async fetch(req) {
+        refreshCalls += 1;
+        refreshBody = await req.text();
+        return Response.json({ success: true, data: { oauth_token: 'fresh-token', expires_in: 3600 } });
+    },
+});
+const tokensPath = path.join(tmpDir, 'tokens.json');
+
+afterAll(() => server.stop());
+
+describe('TokenRefresher', () => {
+    it('should refresh a short-lived token before it expires', async () => {
+        const expiresAt = Date.now() + 2000;
+        fs.writeFileSync(tokensPath, JSON.stringify({
+            oauth_token: 'old-token',
+            refresh_token: 'refresh-1',
+            expires_at: new Date(expiresAt).toISOString(),
+        }));
+        const logSpy = spyOn(console, 'log').mockImplementation(() => {});
+
+        let onRefreshed: (token: string) => void = () => {};
+        const refreshed = new Promise<{ token: string; at: number }>(resolve => {
+            onRefreshed = token => resolve({ token, at: Date.now() });
+        });
+        const refresher = new TokenRefresher({
+            refreshUrl: `http://localhost:${server.port}/auth/refresh`,
+            leadTimeMs: 1900,
+            minDelayMs: 0,
+            onRefreshed: token => onRefreshed(token),
+        });
+        try {
+            expect(refresher.start()).toBe(true);
+
+            const { token, at } = await refreshed;
+            expect(token).toBe('fresh-token');
+            expect(at).toBeLessThan(expiresAt);
+            expect(new URLSearchParams(refreshBody).get('refresh_token')).toBe('refresh-1');
+
+            const saved = JSON.parse(fs.readFileSync(tokensPath, 'utf-8'));
+            expect(saved.oauth_token).toBe('fresh-token');
+            expect(saved.refresh_token).toBe('refresh-1');
+            expect(Date.parse(saved.expires_at)).toBeGreaterThan(expiresAt);
+            expect(refresher.isRunning()).toBe(true);
+        } finally {
+            refresher.stop();
+            logSpy.mockRestore();
+        }
+    });
+
+    it('should wait the minimum delay when the token lives shorter than the lead time', async () => {
+        fs.writeFileSync(tokensPath, JSON.stringify({
+            oauth_token: 'short',
+            refresh_token: 'refresh-2',
+            expires_at: new Date(Date.now() + 1000).toISOString(),
+        }));
+        const logSpy = spyOn(console, 'log').mockImplementation(() => {});
+        const refresher = new TokenRefresher({
+            refreshUrl: `http://localhost:${server.port}/auth/refresh`,
+            leadTimeMs: 60_000,
+            minDelayMs: 10_000,
+            onRefreshed: () => {},
+        });
+        const callsBefore = refreshCalls;
+        try {
+            expect(refresher.start()).toBe(true);
+            await new Promise(resolve => setTimeout(resolve, 50));
+
+            expect(refreshCalls).toBe(callsBefore);
+            expect(refresher.isRunning()).toBe(true);
+        } finally {
+            refresher.stop();
+            logSpy.mockRestore();
+        }
+    });
+
+    it('should not schedule anything without a refresh token', () => {
+        fs.writeFileSync(tokensPath, JSON.stringify({ oauth_token: 'x', expires_at: new Date(Date.now() + 60000).toISOString() }));
+        const refresher = new TokenRefresher({ onRefreshed: () => {} });
+        expect(refresher.start()).toBe(false);
+        expect(refresher.isRunning()).toBe(false);
+    });
+});
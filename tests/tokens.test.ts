@@ -0,0 +1,82 @@
+import { describe, it, expect } from 'bun:test';
+import { mockElectron } from './helpers/electron';
+
+mockElectron('tokens');
+
+const { extractOauthToken, extractAuthData, withTokenExpiry } = await import('../src/utils/fileUtils');
+const { parseTokenResponse } = await import('../src/auth/tokenResponse');
+const { AuthError } = await import('../src/auth/errors');
+
+describe('extractOauthToken', () => {
+    it('should read a top-level oauth_token', () => {
+        expect(extractOauthToken({ oauth_token: 'top' })).toBe('top');
+    });
+
+    it('should read data.oauth_token', () => {
+        expect(extractOauthToken({ success: true, data: { oauth_token: 'nested' } })).toBe('nested');
+    });
+
+    it('should read data.access_token', () => {
+        expect(extractOauthToken({ success: true, data: { access_token: 'access' } })).toBe('access');
+    });
+
+    it('should return null when no token is present', () => {
+        expect(extractOauthToken({ success: false, data: {} })).toBeNull();
+        expect(extractOauthToken(null)).toBeNull();
+        expect(extractOauthToken('oauth_token')).toBeNull();
+    });
+});
+
+describe('withTokenExpiry', () => {
+    const now = Date.parse('2025-01-01T00:00:00.000Z');
+
+    it('should convert expires_in seconds into a future expires_at', () => {
+        const data = withTokenExpiry({ oauth_token: 't', expires_in: 3600 }, now);
+        expect(data.expires_at).toBe('2025-01-01T01:00:00.000Z');
+        expect(Date.parse(data.expires_at as string)).toBeGreaterThan(now);
+    });
+
+    it('should normalize an epoch-seconds expires_at', () => {
+        expect(withTokenExpiry({ expires_at: 1735693200 }, now).expires_at).toBe('2025-01-01T01:00:00.000Z');
+    });
+
+    it('should leave data without expiry unchanged', () => {
+        expect(withTokenExpiry({ oauth_token: 't' }, now)).toEqual({ oauth_token: 't' });
+    });
+});
+
+describe('parseTokenResponse', () => {
+    it('should parse a flat response', () => {
+        expect(parseTokenResponse({ oauth_token: 'flat', expires_in: 10 })).toEqual({
+            oauthToken: 'flat',
+            data: { oauth_token: 'flat', expires_in: 10 },
+        });
+    });
+
+    it('should parse nested oauth_token and access_token responses', () => {
+        expect(parseTokenResponse({ success: true, data: { oauth_token: 'nested' } })).toEqual({
+            oauthToken: 'nested',
+            data: { oauth_token: 'nested' },
+        });
+        expect(parseTokenResponse({ success: true, data: { access_token: 'access' } }).oauthToken).toBe('access');
+    });
+
+    it('should name the missing field and the keys that were found', () => {
+        const error = (() => { try { parseTokenResponse({ success: true, data: { token: 'renamed' } }); } catch (e) { return e; } })() as any;
+        expect(error).toBeInstanceOf(AuthError);
+        expect(error.kind).toBe('token_missing');
+        expect(error.message).toContain('data.oauth_token');
+        expect(error.message).toContain('found: success, data, data.token');
+    });
+
+    it('should keep the flat payload when an unrelated data object is present', () => {
+        const payload = { oauth_token: 'flat', data: { region: 'eu' } };
+        expect(parseTokenResponse(payload)).toEqual({ oauthToken: 'flat', data: payload });
+        expect(extractAuthData(payload)).toEqual(payload);
+    });
+
+    it('should reject non-object and failed responses', () => {
+        expect(() => parseTokenResponse('oauth_token')).toThrow('expected a JSON object, got string');
+        expect(() => parseTokenResponse({ success: false, data: { oauth_token: 'x' } })).toThrow('success is false');
+    });
+});
@@ -0,0 +1,94 @@
+import { describe, it, expect } from 'bun:test';
+import { extractAuthCode, classifyUrl, canonicalize } from '../src/auth/urlUtils';
+
+// Small deterministic PRNG so failures are reproducible
+function createRandom(seed: number) {
+    return () => {
+        seed = (seed * 1103515245 + 12345) % 2147483648;
+        return seed / 2147483648;
+    };
+}
+
+const ALPHABET = 'abcXYZ019-_.~%?&=#/:@ +ñé中😀\u0000';
+const CODE_ALPHABET = 'abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-_';
+
+function randomString(random: () => number, alphabet: string, maxLength: number): string {
+    const chars = Array.from(alphabet);
+    const length = Math.floor(random() * maxLength);
+    let out = '';
+    for (let i = 0; i < length; i++) {
+        out += chars[Math.floor(random() * chars.length)];
+    }
+    return out;
+}
+
+const REGRESSION_SEEDS = [
+    '',
+    'code=',
+    '?code=',
+    'https://streamlabs.com/dashboard?code=',
+    'https://streamlabs.com/dashboard?code=%E0%A4%A',
+    'https://streamlabs.com/dashboard?code=ñ😀&success=true',
+    'http://[::1',
+    'https://www.tiktok.com/foryou?code=abc',
+];
+
+describe('urlUtils fuzzing', () => {
+    it('should never throw on arbitrary input', () => {
+        const random = createRandom(42);
+        const inputs = [...REGRESSION_SEEDS];
+        for (let i = 0; i < 500; i++) {
+            inputs.push(randomString(random, ALPHABET, 60));
+            inputs.push(`https://streamlabs.com/dashboard?${randomString(random, ALPHABET, 40)}`);
+        }
+
+        for (const input of inputs) {
+            expect(() => extractAuthCode(input)).not.toThrow();
+            expect(() => classifyUrl(input)).not.toThrow();
+
+            const code = extractAuthCode(input);
+            if (code !== null) {
+                expect(code.length).toBeGreaterThan(0);
+                expect(input).toContain('code=');
+            }
+        }
+    });
+
+    it('should return URL-safe codes verbatim as a substring of the input', () => {
+        const random = createRandom(7);
+        for (let i = 0; i < 200; i++) {
+            const code = randomString(random, CODE_ALPHABET, 40) || 'x';
+            const url = `https://streamlabs.com/dashboard?lang=${randomString(random, CODE_ALPHABET, 10)}&code=${code}`;
+
+            expect(extractAuthCode(url)).toBe(code);
+            expect(url).toContain(code);
+            expect(classifyUrl(url)).toEqual({ kind: 'auth_success', code });
+        }
+    });
+});
+
+describe('UrlType', () => {
+    it('should round-trip every variant through JSON', () => {
+        const variants = [
+            classifyUrl('https://streamlabs.com/dashboard?code=abc'),
+            classifyUrl('https://www.tiktok.com/foryou'),
+            classifyUrl('https://www.tiktok.com/login'),
+        ];
+        expect(variants.map(v => v.kind)).toEqual(['auth_success', 'tiktok_logged_in', 'other']);
+
+        for (const variant of variants) {
+            expect(JSON.parse(JSON.stringify(variant))).toEqual(variant);
+        }
+    });
+});
+
+describe('canonicalize', () => {
+    it('should strip utm_* and tracking params while keeping code', () => {
+        const url = 'https://streamlabs.com/dashboard?utm_source=x&utm_medium=y&lang=en&code=abc&state=s';
+        expect(canonicalize(url)).toBe('https://streamlabs.com/dashboard?code=abc&state=s');
+    });
+
+    it('should return unparseable input unchanged', () => {
+        expect(canonicalize('not a url')).toBe('not a url');
+    });
+});
@@ -0,0 +1,20 @@
+import { describe, it, expect } from 'bun:test';
+import fs from 'fs';
+import path from 'path';
+import { mockElectron } from './helpers/electron';
+
+mockElectron('version');
+
+const { getVersionInfo } = await import('../src/utils/versionInfo');
+const { CREDENTIAL_FORMAT_VERSION } = await import('../src/utils/fileUtils');
+
+describe('getVersionInfo', () => {
+    it('should report the package.json version and credential format', () => {
+        const pkg = JSON.parse(fs.readFileSync(path.join(import.meta.dir, '..', 'package.json'), 'utf-8'));
+        const info = getVersionInfo();
+
+        expect(info.appVersion).toBe(pkg.version);
+        expect(info.credentialFormatVersion).toBe(CREDENTIAL_FORMAT_VERSION);
+        expect(info.nodeVersion).toBe(process.versions.node);
+    });
+});